@@ -0,0 +1,35 @@
+//! Pit every computer strategy against every other, without a browser:
+//!
+//! ```sh
+//! cargo run -p counter-core --example rps_tournament -- [rounds] [seed] [variant]
+//! ```
+//!
+//! `variant` is `classic`, `lizard-spock` or `cyclic-<n>`. Each cell is the row strategy's
+//! win rate against the column strategy.
+
+use counter_core::rng::SeededRandom;
+use counter_core::rps::Variant;
+use counter_core::strategies::{play_off, Strategy};
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let rounds = args.next().and_then(|value| value.parse().ok()).unwrap_or(1000);
+    let seed = args.next().and_then(|value| value.parse().ok()).unwrap_or(1);
+    let variant = args.next().map(|slug| Variant::from_slug(&slug)).unwrap_or(Variant::Classic);
+    let mut random = SeededRandom::new(seed);
+
+    println!("{}, {} rounds per pairing, seed {}", variant.label(), rounds, seed);
+    print!("{:>30}", "");
+    for column in Strategy::ALL {
+        print!(" {:>10}", column.slug());
+    }
+    println!();
+    for row in Strategy::ALL {
+        print!("{:>30}", row.label());
+        for column in Strategy::ALL {
+            let record = play_off(variant, row, column, rounds, &mut random);
+            print!(" {:>9.1}%", record.win_rate().unwrap_or(0.0) * 100.0);
+        }
+        println!();
+    }
+}
@@ -0,0 +1,393 @@
+use crate::cats::{
+    CatQuery, CatSource, CatWindow, ImageLoad, ImageSlot, ImageStatus, LoadTimer, PreloadSettings, RetryDecision,
+    RetryPolicy,
+};
+use crate::platform::Platform;
+
+/// What the platform has to do with image elements for the bookkeeping to hold up
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageAction {
+    /// Start loading `url` into `slot`; answer with `image_loaded` or `image_failed`,
+    /// passing `generation` back
+    Load { generation: u32, slot: ImageSlot, url: String },
+    /// Call `retry` for `slot` after `delay_ms` milliseconds, passing `generation` back
+    RetryAfter { generation: u32, slot: ImageSlot, delay_ms: u32 },
+    /// Drop every current image and make room for `count` new ones
+    Reset(usize),
+    /// Drop the oldest `count` current images
+    Evict(usize),
+    /// Drop the next batch's images and make room for `count` new ones
+    PrepareNextBatch(usize),
+    /// Move the next batch's images behind the current ones
+    CommitNextBatch,
+}
+
+/// The cats in memory and how far along their images are.
+///
+/// Cats come from the source in batches. The first batch has to load before browsing starts;
+/// after that the next batch preloads in the background once the current cat gets close to
+/// the last one, and joins the browsable cats once every image in it has settled.
+pub struct CatBatches {
+    pub source: Box<dyn CatSource>,
+    pub window: CatWindow,
+    pub settings: PreloadSettings,
+    pub timer: LoadTimer,
+    pub retry_policy: RetryPolicy,
+    /// Cats that can be browsed
+    pub urls: Vec<String>,
+    pub current_index: usize,
+    /// Cats dropped from the front of `urls`, so positions stay stable for display and links
+    pub evicted: usize,
+    pub loads: Vec<ImageLoad>,
+    /// The first batch is still loading
+    pub loading: bool,
+    pub loaded_count: usize,
+    pub total_ever_loaded: usize,
+    pub preloading_next_batch: bool,
+    pub next_batch_urls: Vec<String>,
+    pub next_batch_loads: Vec<ImageLoad>,
+    pub next_batch_loaded_count: usize,
+    /// Bumped whenever the loaded cats are thrown away, so callbacks for images of an
+    /// earlier search can be told apart from the ones for the slots that replaced them
+    pub generation: u32,
+    actions: Vec<ImageAction>,
+}
+
+impl CatBatches {
+    pub fn new(source: Box<dyn CatSource>, window: CatWindow, settings: PreloadSettings) -> Self {
+        CatBatches {
+            source,
+            window,
+            settings,
+            timer: LoadTimer::default(),
+            retry_policy: RetryPolicy::default(),
+            urls: Vec::new(),
+            current_index: 0,
+            evicted: 0,
+            loads: Vec::new(),
+            loading: false,
+            loaded_count: 0,
+            total_ever_loaded: 0,
+            preloading_next_batch: false,
+            next_batch_urls: Vec::new(),
+            next_batch_loads: Vec::new(),
+            next_batch_loaded_count: 0,
+            generation: 0,
+            actions: Vec::new(),
+        }
+    }
+
+    /// What the platform has to do with image elements since the last call
+    pub fn take_actions(&mut self) -> Vec<ImageAction> {
+        std::mem::take(&mut self.actions)
+    }
+
+    pub fn current_url(&self) -> Option<&String> {
+        self.urls.get(self.current_index)
+    }
+
+    /// 0-based position of the current cat since the browser started
+    pub fn position(&self) -> usize {
+        self.evicted + self.current_index
+    }
+
+    /// Load the first batch, led by the cat a link named with `linked` if the source
+    /// can bring it back
+    pub fn start(&mut self, linked: Option<&str>, platform: &dyn Platform) {
+        // Callbacks still due for the images dropped here no longer match
+        self.generation = self.generation.wrapping_add(1);
+        self.loading = true;
+        self.loaded_count = 0;
+        self.current_index = 0;
+        if self.preloading_next_batch {
+            self.preloading_next_batch = false;
+            self.next_batch_urls.clear();
+            self.next_batch_loads.clear();
+            self.actions.push(ImageAction::PrepareNextBatch(0));
+        }
+
+        let batch_size = self.window.batch_size(self.effective().batch_size, 0);
+        self.urls = match linked.and_then(|id| self.source.linked_cat(id)) {
+            Some(url) => {
+                let mut urls = vec![url];
+                urls.extend(self.source.next_batch(batch_size.saturating_sub(1)));
+                urls
+            }
+            None => self.source.next_batch(batch_size),
+        };
+        self.evicted = 0;
+        self.total_ever_loaded = self.urls.len();
+        self.actions.push(ImageAction::Reset(self.urls.len()));
+        self.loads = vec![ImageLoad::new(); self.urls.len()];
+
+        // No image would ever settle the batch
+        if self.urls.is_empty() {
+            platform.log(&format!("The {} source has no cats to show", self.source.name()));
+            self.loading = false;
+            return;
+        }
+        platform.log(&format!("Starting to preload {} images", self.urls.len()));
+        self.start_queued_loads(platform);
+    }
+
+    /// Search for different cats, replacing everything loaded for the old search.
+    /// Returns false if `query` is already the current search.
+    pub fn set_query(&mut self, query: CatQuery, platform: &dyn Platform) -> bool {
+        if self.source.query() == Some(&query) {
+            return false;
+        }
+        platform.log(&format!("Searching cats: {:?}", query));
+        self.source.set_query(query);
+        self.start(None, platform);
+        true
+    }
+
+    /// What a link to the current cat names it by, if the source can bring it back
+    pub fn current_link_id(&self) -> Option<String> {
+        self.source.link_id(self.current_url()?)
+    }
+
+    /// Jump to the cat a link named with `id` if it is in memory
+    pub fn show_linked(&mut self, id: &str) -> bool {
+        match self.urls.iter().position(|url| self.source.link_id(url).as_deref() == Some(id)) {
+            Some(index) => {
+                self.current_index = index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move on to the next cat, wrapping around at the end
+    pub fn next(&mut self, platform: &dyn Platform) {
+        if self.urls.is_empty() {
+            return;
+        }
+        self.current_index = (self.current_index + 1) % self.urls.len();
+        self.evict_old(platform);
+        self.prefetch_if_needed(platform);
+    }
+
+    /// Go back a cat; returns false at the first cat in memory
+    pub fn previous(&mut self) -> bool {
+        if self.current_index == 0 {
+            return false;
+        }
+        self.current_index -= 1;
+        true
+    }
+
+    /// Show the cat at 0-based `position`, clamped to the cats still in memory
+    pub fn show_at(&mut self, position: usize) {
+        if self.urls.is_empty() {
+            return;
+        }
+        let first = self.evicted;
+        let last = self.evicted + self.urls.len() - 1;
+        self.current_index = position.clamp(first, last) - first;
+    }
+
+    /// Drop cats that fell more than `window.behind` behind the current one
+    pub fn evict_old(&mut self, platform: &dyn Platform) {
+        let evict = self.window.evictable(self.current_index);
+        if evict == 0 {
+            return;
+        }
+        self.urls.drain(..evict);
+        self.actions.push(ImageAction::Evict(evict));
+        self.loads.drain(..evict);
+        self.current_index -= evict;
+        self.evicted += evict;
+        platform.log(&format!("Evicted {} old cats, {} kept in memory", evict, self.urls.len()));
+    }
+
+    /// Start preloading the next batch once the current cat is close to the last one loaded
+    pub fn prefetch_if_needed(&mut self, platform: &dyn Platform) {
+        if self.urls.is_empty() {
+            return;
+        }
+        let settings = self.effective();
+        let loaded_ahead = self.urls.len() - 1 - self.current_index;
+        let approaching_end = loaded_ahead <= settings.look_ahead;
+        let batch_size = self.window.batch_size(settings.batch_size, loaded_ahead);
+        if approaching_end && !self.preloading_next_batch && batch_size > 0 {
+            platform.log("Approaching end of batch - starting to preload next batch");
+            self.preloading_next_batch = true;
+            self.next_batch_loaded_count = 0;
+            // Committed to `urls` once every image has settled
+            self.next_batch_urls = self.source.next_batch(batch_size);
+
+            platform.log(&format!("Starting to preload next batch of {} images in background...", self.next_batch_urls.len()));
+            self.actions.push(ImageAction::PrepareNextBatch(self.next_batch_urls.len()));
+            self.next_batch_loads = vec![ImageLoad::new(); self.next_batch_urls.len()];
+            self.start_queued_loads(platform);
+        }
+    }
+
+    /// Preload settings adjusted to the load times measured so far
+    pub fn effective(&self) -> PreloadSettings {
+        self.settings.effective(self.timer.average_ms())
+    }
+
+    pub fn slot_url(&self, slot: ImageSlot) -> Option<&String> {
+        match slot {
+            ImageSlot::Current(index) => self.urls.get(index),
+            ImageSlot::NextBatch(index) => self.next_batch_urls.get(index),
+        }
+    }
+
+    pub fn image_load(&self, slot: ImageSlot) -> Option<&ImageLoad> {
+        match slot {
+            ImageSlot::Current(index) => self.loads.get(index),
+            ImageSlot::NextBatch(index) => self.next_batch_loads.get(index),
+        }
+    }
+
+    fn image_load_mut(&mut self, slot: ImageSlot) -> Option<&mut ImageLoad> {
+        match slot {
+            ImageSlot::Current(index) => self.loads.get_mut(index),
+            ImageSlot::NextBatch(index) => self.next_batch_loads.get_mut(index),
+        }
+    }
+
+    /// Start queued images, current batch first, until `max_concurrent` are downloading
+    pub fn start_queued_loads(&mut self, platform: &dyn Platform) {
+        let max_concurrent = self.effective().max_concurrent;
+        let loading = self.loads.iter().chain(&self.next_batch_loads)
+            .filter(|load| load.status == ImageStatus::Loading)
+            .count();
+        let queued: Vec<ImageSlot> = (0..self.loads.len()).map(ImageSlot::Current)
+            .chain((0..self.next_batch_loads.len()).map(ImageSlot::NextBatch))
+            .filter(|slot| self.image_load(*slot).is_some_and(|load| load.status == ImageStatus::Queued))
+            .collect();
+
+        let now = platform.now_ms();
+        for slot in queued.into_iter().take(max_concurrent.saturating_sub(loading)) {
+            if let Some(load) = self.image_load_mut(slot) {
+                load.status = ImageStatus::Loading;
+                load.started_at = now;
+            }
+            if let Some(url) = self.slot_url(slot).cloned() {
+                self.actions.push(ImageAction::Load { generation: self.generation, slot, url });
+            }
+        }
+    }
+
+    /// Count a slot that has loaded or been given up on towards its batch's progress
+    fn image_settled(&mut self, slot: ImageSlot, platform: &dyn Platform) {
+        match slot {
+            ImageSlot::Current(_) => {
+                self.loaded_count += 1;
+                platform.log(&format!("Preloaded {}/{} images", self.loaded_count, self.urls.len()));
+                if self.loaded_count >= self.urls.len() {
+                    platform.log("All images preloaded successfully!");
+                    self.loading = false;
+                }
+            }
+            ImageSlot::NextBatch(_) => {
+                self.next_batch_loaded_count += 1;
+                platform.log(&format!("Next batch image settled: {}/{}", self.next_batch_loaded_count, self.next_batch_urls.len()));
+                if self.next_batch_loaded_count >= self.next_batch_urls.len() {
+                    self.commit_next_batch(platform);
+                }
+            }
+        }
+    }
+
+    fn commit_next_batch(&mut self, platform: &dyn Platform) {
+        platform.log("All next batch images preloaded! Adding to available cats.");
+        self.urls.append(&mut self.next_batch_urls);
+        self.actions.push(ImageAction::CommitNextBatch);
+        self.loads.append(&mut self.next_batch_loads);
+        self.total_ever_loaded = self.evicted + self.urls.len();
+        self.preloading_next_batch = false;
+        self.next_batch_loaded_count = 0;
+        platform.log(&format!("Total cats now available: {}", self.urls.len()));
+    }
+
+    /// Mark `slot` as loaded, ignoring callbacks from batches that have since been replaced
+    pub fn image_loaded(&mut self, generation: u32, slot: ImageSlot, platform: &dyn Platform) {
+        if generation != self.generation {
+            return;
+        }
+        let in_flight = match slot {
+            ImageSlot::Current(_) => self.loading,
+            ImageSlot::NextBatch(_) => self.preloading_next_batch,
+        };
+        let started_at = match self.image_load_mut(slot) {
+            Some(load) if in_flight && !load.is_settled() => {
+                load.status = ImageStatus::Loaded;
+                load.started_at
+            }
+            _ => return,
+        };
+        self.timer.record(platform.now_ms() - started_at);
+        if let Some(url) = self.slot_url(slot).cloned() {
+            self.source.image_loaded(&url);
+        }
+        self.start_queued_loads(platform);
+        self.image_settled(slot, platform);
+    }
+
+    /// Retry, replace or give up on `slot`, ignoring callbacks from batches that have since
+    /// been replaced
+    pub fn image_failed(&mut self, generation: u32, slot: ImageSlot, platform: &dyn Platform) {
+        if generation != self.generation {
+            return;
+        }
+        let in_flight = match slot {
+            ImageSlot::Current(_) => self.loading,
+            ImageSlot::NextBatch(_) => self.preloading_next_batch,
+        };
+        let policy = self.retry_policy;
+        let decision = match self.image_load_mut(slot) {
+            Some(load) if in_flight && !load.is_settled() => load.record_failure(&policy),
+            _ => return,
+        };
+        if let Some(url) = self.slot_url(slot).cloned() {
+            self.source.image_failed(&url);
+        }
+
+        match decision {
+            RetryDecision::RetryIn(delay_ms) => {
+                platform.log(&format!("Retrying {:?} in {}ms", slot, delay_ms));
+                self.actions.push(ImageAction::RetryAfter { generation, slot, delay_ms });
+                self.start_queued_loads(platform);
+            }
+            RetryDecision::Replace => {
+                let Some(url) = self.source.next_batch(1).pop() else {
+                    platform.log(&format!("No fresh cat to replace {:?} with, giving up on it", slot));
+                    if let Some(load) = self.image_load_mut(slot) {
+                        load.status = ImageStatus::Failed;
+                    }
+                    self.start_queued_loads(platform);
+                    return self.image_settled(slot, platform);
+                };
+                platform.log(&format!("Replacing {:?} with a fresh cat: {}", slot, url));
+                match slot {
+                    ImageSlot::Current(index) => self.urls[index] = url,
+                    ImageSlot::NextBatch(index) => self.next_batch_urls[index] = url,
+                }
+                self.start_queued_loads(platform);
+            }
+            RetryDecision::GiveUp => {
+                platform.log(&format!("Giving up on {:?}", slot));
+                self.start_queued_loads(platform);
+                self.image_settled(slot, platform);
+            }
+        }
+    }
+
+    /// Queue `slot` again once its retry delay has passed
+    pub fn retry(&mut self, generation: u32, slot: ImageSlot, platform: &dyn Platform) {
+        if generation != self.generation {
+            return;
+        }
+        if let Some(load) = self.image_load_mut(slot) {
+            if load.status == ImageStatus::Retrying {
+                load.status = ImageStatus::Queued;
+                self.start_queued_loads(platform);
+            }
+        }
+    }
+}
@@ -0,0 +1,553 @@
+use crate::platform::Log;
+use crate::routes::{percent_encode, Query};
+
+/// Where cat image URLs come from.
+///
+/// The preloader only ever asks for the next batch of URLs, so a source can be
+/// backed by a remote API, files shipped next to `index.html`, or generated data.
+pub trait CatSource {
+    /// Short name for logs
+    fn name(&self) -> &'static str;
+
+    /// URLs for the next `count` cats
+    fn next_batch(&mut self, count: usize) -> Vec<String>;
+
+    /// Called once the image at `url` has loaded
+    fn image_loaded(&mut self, _url: &str) {}
+
+    /// Called each time loading the image at `url` fails
+    fn image_failed(&mut self, _url: &str) {}
+
+    /// What the source knows about the cat at `url`
+    fn metadata(&self, _url: &str) -> Option<CatMetadata> {
+        None
+    }
+
+    /// Short name a link can bring the cat at `url` back with, if the source can do that
+    fn link_id(&self, url: &str) -> Option<String> {
+        self.metadata(url).map(|metadata| metadata.id)
+    }
+
+    /// URL of the cat a link named with `link_id`
+    fn linked_cat(&mut self, _id: &str) -> Option<String> {
+        None
+    }
+
+    /// The current search, for sources that can be searched
+    fn query(&self) -> Option<&CatQuery> {
+        None
+    }
+
+    /// Only hand out cats matching `query` from now on; ignored by sources that can't search
+    fn set_query(&mut self, _query: CatQuery) {}
+
+    /// I/O the source needs done since the last call, carried out by the platform
+    fn take_requests(&mut self) -> Vec<SourceRequest> {
+        Vec::new()
+    }
+
+    /// Answer to a `SourceRequest::FetchMetadata`; `None` if the fetch failed
+    fn metadata_fetched(&mut self, _generation: u32, _metadata: Option<CatMetadata>) {}
+}
+
+/// Something a source can't do by itself without blocking or touching the browser
+#[derive(Debug, Clone, PartialEq)]
+pub enum SourceRequest {
+    /// Fetch cataas metadata JSON from `url`; answer with `metadata_fetched`, passing
+    /// `generation` back
+    FetchMetadata { url: String, generation: u32 },
+    /// Keep the loaded image at `url` for offline browsing
+    Store(String),
+}
+
+/// Image filters cataas can apply
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CatFilter {
+    None,
+    Mono,
+    Negate,
+    Blur,
+    Sepia,
+    Paint,
+    Pixel,
+}
+
+impl CatFilter {
+    pub const ALL: [CatFilter; 7] = [
+        CatFilter::None,
+        CatFilter::Mono,
+        CatFilter::Negate,
+        CatFilter::Blur,
+        CatFilter::Sepia,
+        CatFilter::Paint,
+        CatFilter::Pixel,
+    ];
+
+    /// Value of the `filter` query parameter, also used as the option value in the panel
+    pub fn slug(&self) -> &'static str {
+        match self {
+            CatFilter::None => "",
+            CatFilter::Mono => "mono",
+            CatFilter::Negate => "negate",
+            CatFilter::Blur => "blur",
+            CatFilter::Sepia => "sepia",
+            CatFilter::Paint => "paint",
+            CatFilter::Pixel => "pixel",
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Self {
+        Self::ALL.into_iter().find(|filter| filter.slug() == slug).unwrap_or(CatFilter::None)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CatFilter::None => "No filter",
+            CatFilter::Mono => "Mono",
+            CatFilter::Negate => "Negate",
+            CatFilter::Blur => "Blur",
+            CatFilter::Sepia => "Sepia",
+            CatFilter::Paint => "Paint",
+            CatFilter::Pixel => "Pixel",
+        }
+    }
+}
+
+/// Image sizes cataas can serve
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CatSize {
+    Original,
+    Square,
+    Medium,
+    Small,
+    ExtraSmall,
+}
+
+impl CatSize {
+    pub const ALL: [CatSize; 5] = [CatSize::Original, CatSize::Square, CatSize::Medium, CatSize::Small, CatSize::ExtraSmall];
+
+    /// Value of the `type` query parameter, also used as the option value in the panel
+    pub fn slug(&self) -> &'static str {
+        match self {
+            CatSize::Original => "",
+            CatSize::Square => "square",
+            CatSize::Medium => "medium",
+            CatSize::Small => "small",
+            CatSize::ExtraSmall => "xsmall",
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Self {
+        Self::ALL.into_iter().find(|size| size.slug() == slug).unwrap_or(CatSize::Original)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CatSize::Original => "Original size",
+            CatSize::Square => "Square",
+            CatSize::Medium => "Medium",
+            CatSize::Small => "Small",
+            CatSize::ExtraSmall => "Extra small",
+        }
+    }
+}
+
+/// What kind of cats to ask a source for; empty fields mean "any"
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatQuery {
+    pub tag: String,
+    /// Caption written over the image
+    pub says: String,
+    pub filter: CatFilter,
+    pub size: CatSize,
+}
+
+impl Default for CatQuery {
+    fn default() -> Self {
+        CatQuery { tag: String::new(), says: String::new(), filter: CatFilter::None, size: CatSize::Original }
+    }
+}
+
+impl CatQuery {
+    /// Path and query of a cataas request: `/cat[/<tag or id>][/says/<text>]?type=..&filter=..`.
+    /// With an `id` the request names one specific cat, otherwise cataas picks a random one.
+    pub fn cataas_url(&self, id: Option<&str>, extra: &[(&str, String)]) -> String {
+        let mut path = "/cat".to_string();
+        match id {
+            Some(id) => path.push_str(&format!("/{}", percent_encode(id))),
+            None if !self.tag.trim().is_empty() => path.push_str(&format!("/{}", percent_encode(self.tag.trim()))),
+            None => {}
+        }
+        if !self.says.trim().is_empty() {
+            path.push_str(&format!("/says/{}", percent_encode(self.says.trim())));
+        }
+
+        let mut params = Vec::new();
+        if self.size != CatSize::Original {
+            params.push(("type".to_string(), self.size.slug().to_string()));
+        }
+        if self.filter != CatFilter::None {
+            params.push(("filter".to_string(), self.filter.slug().to_string()));
+        }
+        params.extend(extra.iter().map(|(key, value)| (key.to_string(), value.clone())));
+        format!("{}{}", path, Query::from_pairs(params))
+    }
+}
+
+/// What cataas knows about a cat, from `?json=true`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatMetadata {
+    pub id: String,
+    pub tags: Vec<String>,
+}
+
+/// A fixed list of image files served alongside the app, cycled through forever
+pub struct LocalSource {
+    files: Vec<String>,
+    cursor: usize,
+}
+
+impl LocalSource {
+    pub fn new(files: Vec<String>) -> Self {
+        LocalSource { files, cursor: 0 }
+    }
+}
+
+impl CatSource for LocalSource {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn next_batch(&mut self, count: usize) -> Vec<String> {
+        if self.files.is_empty() {
+            return Vec::new();
+        }
+        (0..count)
+            .map(|_| {
+                let file = self.files[self.cursor % self.files.len()].clone();
+                self.cursor += 1;
+                file
+            })
+            .collect()
+    }
+
+    /// Files are linked by their path
+    fn link_id(&self, url: &str) -> Option<String> {
+        Some(url.to_string())
+    }
+
+    fn linked_cat(&mut self, id: &str) -> Option<String> {
+        self.files.iter().find(|file| *file == id).cloned()
+    }
+}
+
+/// Generated placeholder cats as inline SVG, identical on every run and needing no network
+#[derive(Default)]
+pub struct FixtureSource {
+    issued: usize,
+}
+
+impl FixtureSource {
+    pub fn new() -> Self {
+        FixtureSource { issued: 0 }
+    }
+
+    fn image_url(number: usize) -> String {
+        let hue = (number * 47) % 360;
+        let svg = format!(
+            "<svg xmlns='http://www.w3.org/2000/svg' width='400' height='400' viewBox='0 0 400 400'>\
+             <rect width='400' height='400' fill='hsl({hue}, 70%, 80%)'/>\
+             <text x='200' y='215' font-size='48' text-anchor='middle' font-family='sans-serif' \
+             fill='hsl({hue}, 60%, 30%)'>Cat {number}</text></svg>"
+        );
+        let escaped = svg.replace('%', "%25").replace('#', "%23").replace('<', "%3C").replace('>', "%3E");
+        format!("data:image/svg+xml,{}", escaped)
+    }
+}
+
+impl CatSource for FixtureSource {
+    fn name(&self) -> &'static str {
+        "fixture"
+    }
+
+    fn next_batch(&mut self, count: usize) -> Vec<String> {
+        (0..count)
+            .map(|_| {
+                self.issued += 1;
+                Self::image_url(self.issued)
+            })
+            .collect()
+    }
+
+    /// Fixtures are linked by their number
+    fn link_id(&self, url: &str) -> Option<String> {
+        let number = url.split("%3ECat ").nth(1)?.split("%3C").next()?;
+        let parsed = number.parse::<usize>().ok()?;
+        (Self::image_url(parsed) == url).then(|| number.to_string())
+    }
+
+    fn linked_cat(&mut self, id: &str) -> Option<String> {
+        id.parse::<usize>().ok().filter(|&number| number >= 1).map(Self::image_url)
+    }
+}
+
+/// How many cats to keep around the one being shown; everything older is evicted
+#[derive(Debug, Clone, Copy)]
+pub struct CatWindow {
+    /// Cats kept before the current one, for going back
+    pub behind: usize,
+    /// Most cats preloaded past the current one
+    pub ahead: usize,
+}
+
+impl Default for CatWindow {
+    fn default() -> Self {
+        CatWindow { behind: 10, ahead: 20 }
+    }
+}
+
+impl CatWindow {
+    /// Parse the content of `<meta name="cat-window" content="behind=10, ahead=20">`;
+    /// missing keys keep their defaults
+    pub fn parse(content: &str, log: &dyn Log) -> Self {
+        let mut cat_window = CatWindow::default();
+        for setting in content.split(',') {
+            let Some((key, value)) = setting.split_once('=') else { continue };
+            let Ok(value) = value.trim().parse::<usize>() else { continue };
+            match key.trim() {
+                "behind" => cat_window.behind = value,
+                "ahead" => cat_window.ahead = value.max(1),
+                _ => log.log(&format!("Unknown cat-window setting: {}", key)),
+            }
+        }
+        cat_window
+    }
+
+    /// Number of cats before `current_index` that fall outside the window
+    pub fn evictable(&self, current_index: usize) -> usize {
+        current_index.saturating_sub(self.behind)
+    }
+
+    /// Size of the next batch, given how many cats are already loaded past the current one
+    pub fn batch_size(&self, requested: usize, loaded_ahead: usize) -> usize {
+        requested.min(self.ahead.saturating_sub(loaded_ahead))
+    }
+}
+
+/// How long a cat is typically looked at, for working out how early to prefetch
+const VIEW_TIME_MS: f64 = 2000.0;
+
+/// Average load time above which the connection counts as slow
+const SLOW_LOAD_MS: f64 = 1500.0;
+
+/// How the preloader fetches cats
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PreloadSettings {
+    /// Cats fetched per batch
+    pub batch_size: usize,
+    /// The next batch starts loading once this many cats or fewer are left ahead
+    pub look_ahead: usize,
+    /// Images downloading at the same time
+    pub max_concurrent: usize,
+    /// Tune the values above from measured load times
+    pub adaptive: bool,
+}
+
+impl Default for PreloadSettings {
+    fn default() -> Self {
+        PreloadSettings { batch_size: 10, look_ahead: 1, max_concurrent: 6, adaptive: true }
+    }
+}
+
+impl PreloadSettings {
+    pub const MAX_BATCH_SIZE: usize = 50;
+    pub const MAX_LOOK_AHEAD: usize = 20;
+    pub const MAX_CONCURRENT: usize = 16;
+
+    /// Take whichever of `batch`, `look-ahead`, `concurrent` and `adaptive=on|off` `query` has
+    pub fn apply_query(&mut self, query: &Query) {
+        if let Some(value) = query.get("batch") {
+            self.set_batch_size(&value);
+        }
+        if let Some(value) = query.get("look-ahead") {
+            self.set_look_ahead(&value);
+        }
+        if let Some(value) = query.get("concurrent") {
+            self.set_max_concurrent(&value);
+        }
+        match query.get("adaptive").as_deref() {
+            Some("on") => self.adaptive = true,
+            Some("off") => self.adaptive = false,
+            _ => {}
+        }
+    }
+
+    /// The settings as a query string, e.g. `?batch=10&look-ahead=1&concurrent=6&adaptive=on`
+    pub fn to_query(self) -> Query {
+        Query::from_pairs(vec![
+            ("batch".to_string(), self.batch_size.to_string()),
+            ("look-ahead".to_string(), self.look_ahead.to_string()),
+            ("concurrent".to_string(), self.max_concurrent.to_string()),
+            ("adaptive".to_string(), if self.adaptive { "on" } else { "off" }.to_string()),
+        ])
+    }
+
+    // Setters take the raw text from an input or URL; anything unparsable is ignored
+    pub fn set_batch_size(&mut self, value: &str) {
+        if let Ok(value) = value.trim().parse::<usize>() {
+            self.batch_size = value.clamp(1, Self::MAX_BATCH_SIZE);
+        }
+    }
+
+    pub fn set_look_ahead(&mut self, value: &str) {
+        if let Ok(value) = value.trim().parse::<usize>() {
+            self.look_ahead = value.clamp(1, Self::MAX_LOOK_AHEAD);
+        }
+    }
+
+    pub fn set_max_concurrent(&mut self, value: &str) {
+        if let Ok(value) = value.trim().parse::<usize>() {
+            self.max_concurrent = value.clamp(1, Self::MAX_CONCURRENT);
+        }
+    }
+
+    /// The settings to actually use, given the average image load time so far.
+    ///
+    /// On a slow connection batches get smaller so they are committed sooner and fewer
+    /// images compete for bandwidth. Look-ahead grows until the next batch should arrive
+    /// before the user has clicked through the cats left ahead.
+    pub fn effective(&self, average_load_ms: Option<f64>) -> PreloadSettings {
+        let Some(average_ms) = average_load_ms.filter(|_| self.adaptive) else {
+            return *self;
+        };
+        let slow = average_ms > SLOW_LOAD_MS;
+        let batch_size = if slow { (self.batch_size / 2).max(3) } else { self.batch_size };
+        let max_concurrent = if slow { (self.max_concurrent / 2).max(2) } else { self.max_concurrent };
+
+        let rounds = batch_size.div_ceil(max_concurrent) as f64;
+        let cats_to_cover = (rounds * average_ms / VIEW_TIME_MS).ceil() as usize;
+        let look_ahead = cats_to_cover.max(self.look_ahead).min(batch_size).clamp(1, Self::MAX_LOOK_AHEAD);
+
+        PreloadSettings { batch_size, look_ahead, max_concurrent, adaptive: true }
+    }
+}
+
+/// Running average of how long cat images take to load
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoadTimer {
+    average_ms: Option<f64>,
+}
+
+impl LoadTimer {
+    /// Weight of the newest sample, so the average follows a changing connection
+    const SMOOTHING: f64 = 0.2;
+
+    pub fn record(&mut self, duration_ms: f64) {
+        self.average_ms = Some(match self.average_ms {
+            Some(average) => average + Self::SMOOTHING * (duration_ms - average),
+            None => duration_ms,
+        });
+    }
+
+    pub fn average_ms(&self) -> Option<f64> {
+        self.average_ms
+    }
+}
+
+/// Which preloaded image a load callback is about
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageSlot {
+    /// Index into the cats that can already be browsed
+    Current(usize),
+    /// Index into the batch being preloaded in the background
+    NextBatch(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageStatus {
+    /// Waiting for a free slot under the concurrent load limit
+    Queued,
+    Loading,
+    /// Waiting to try the same URL again
+    Retrying,
+    Loaded,
+    /// Gave up on this slot, a placeholder is shown instead
+    Failed,
+}
+
+/// How hard to try before giving up on an image
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Tries per URL, including the first one
+    pub max_attempts: u32,
+    /// Fresh URLs to fetch from the source once a URL has used up its attempts
+    pub max_replacements: u32,
+    pub base_delay_ms: u32,
+    pub max_delay_ms: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy { max_attempts: 3, max_replacements: 2, base_delay_ms: 500, max_delay_ms: 8000 }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retrying after the `failures`-th failure: base, 2×base, 4×base, … capped
+    pub fn backoff_ms(&self, failures: u32) -> u32 {
+        let exponent = failures.saturating_sub(1).min(16);
+        self.base_delay_ms.saturating_mul(1 << exponent).min(self.max_delay_ms)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RetryDecision {
+    RetryIn(u32),
+    /// Swap in a fresh URL from the source and start over
+    Replace,
+    GiveUp,
+}
+
+/// Load state of one preloaded image
+#[derive(Debug, Clone, Copy)]
+pub struct ImageLoad {
+    pub status: ImageStatus,
+    /// When the current attempt started, in milliseconds since the epoch
+    pub started_at: f64,
+    /// Failures of the current URL
+    pub failures: u32,
+    pub replacements: u32,
+}
+
+impl Default for ImageLoad {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImageLoad {
+    pub fn new() -> Self {
+        ImageLoad { status: ImageStatus::Queued, started_at: 0.0, failures: 0, replacements: 0 }
+    }
+
+    /// Loaded or given up on; either way it no longer holds its batch back
+    pub fn is_settled(&self) -> bool {
+        matches!(self.status, ImageStatus::Loaded | ImageStatus::Failed)
+    }
+
+    pub fn record_failure(&mut self, policy: &RetryPolicy) -> RetryDecision {
+        self.failures += 1;
+        if self.failures < policy.max_attempts {
+            self.status = ImageStatus::Retrying;
+            RetryDecision::RetryIn(policy.backoff_ms(self.failures))
+        } else if self.replacements < policy.max_replacements {
+            self.replacements += 1;
+            self.failures = 0;
+            self.status = ImageStatus::Queued;
+            RetryDecision::Replace
+        } else {
+            self.status = ImageStatus::Failed;
+            RetryDecision::GiveUp
+        }
+    }
+}
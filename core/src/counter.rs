@@ -0,0 +1,32 @@
+/// Value of the counter page
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Counter {
+    pub value: f64,
+}
+
+impl Counter {
+    pub fn increment(&mut self) {
+        self.value += 1.0;
+    }
+
+    pub fn decrement(&mut self) {
+        self.value -= 1.0;
+    }
+
+    pub fn reset(&mut self) {
+        self.value = 0.0;
+    }
+
+    pub fn double(&mut self) {
+        self.value *= 2.0;
+    }
+
+    pub fn halve(&mut self) {
+        self.value /= 2.0;
+    }
+
+    /// Shows off what floats do: infinity, or NaN for zero
+    pub fn divide_by_zero(&mut self) {
+        self.value /= 0.0;
+    }
+}
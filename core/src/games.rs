@@ -0,0 +1,544 @@
+use std::collections::HashMap;
+
+use crate::guessing::{Difficulty, GuessRules};
+use crate::platform::{Platform, Random};
+use crate::rng::{self, SeededRandom};
+use crate::routes::Query;
+use crate::rps::{Match, Move, Record, Round, Variant};
+use crate::strategies::Strategy;
+
+/// Query parameter of game URLs holding the seed, e.g. `/games/memory?seed=1234`
+pub const SEED_PARAM: &str = "seed";
+
+/// Query parameter picking the number guessing difficulty, e.g. `/games/number-guessing?difficulty=hard`
+pub const DIFFICULTY_PARAM: &str = "difficulty";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameMsg {
+    RockPaperScissorsPlay(Move),
+    SelectRpsVariant(Variant),
+    SelectRpsStrategy(Strategy),
+    /// Play best-of-`n` matches, or rounds without end for `None`
+    SelectRpsMatchLength(Option<u32>),
+    NewRpsMatch,
+    /// Clear every score, the round history and what the computer has learned
+    ResetRps,
+    GuessNumber(i32),
+    GuessInputChanged(String),
+    SubmitGuess,
+    NewGuessGame,
+    /// Start over on a preset, or open the custom settings
+    SelectGuessDifficulty(Difficulty),
+    GuessCustomMinChanged(String),
+    GuessCustomMaxChanged(String),
+    GuessCustomAttemptsChanged(String),
+    /// Start over with the custom settings
+    ApplyGuessCustom,
+    MemoryCardClick(usize),
+    StartMemoryGame,
+    ResetMemoryGame,
+    /// Nothing schedules this yet; the next click hides the pair instead
+    HideMemoryCards,
+    SeedInputChanged(String),
+    /// Restart every game from the seed typed in
+    ApplySeed,
+    /// Restart every game from a fresh seed
+    NewSeed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameKind {
+    RockPaperScissors,
+    NumberGuessing,
+    Memory,
+}
+
+impl GameKind {
+    pub fn from_slug(slug: &str) -> Option<Self> {
+        match slug {
+            "rock-paper-scissors" => Some(GameKind::RockPaperScissors),
+            "number-guessing" => Some(GameKind::NumberGuessing),
+            "memory" => Some(GameKind::Memory),
+            _ => None,
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            GameKind::RockPaperScissors => "Rock Paper Scissors",
+            GameKind::NumberGuessing => "Number Guessing",
+            GameKind::Memory => "Memory Cards",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            GameKind::RockPaperScissors => "Play Rock Paper Scissors against the computer.",
+            GameKind::NumberGuessing => "Guess the secret number with as few attempts as possible.",
+            GameKind::Memory => "Flip cards and find all matching pairs.",
+        }
+    }
+
+    pub fn slug(&self) -> &'static str {
+        match self {
+            GameKind::RockPaperScissors => "rock-paper-scissors",
+            GameKind::NumberGuessing => "number-guessing",
+            GameKind::Memory => "memory",
+        }
+    }
+}
+
+/// The games' state. Every random pick comes from `seed`, so replaying the same moves
+/// after the same seed plays out the same way.
+#[derive(Debug, Clone)]
+pub struct GameState {
+    pub seed: u64,
+    pub seed_input: String,
+    rng: SeededRandom,
+    
+    // Rock Paper Scissors
+    pub rps_variant: Variant,
+    /// Every round played since the last reset, oldest first
+    pub rps_rounds: Vec<Round>,
+    /// The player's record in each variant
+    pub rps_scores: HashMap<Variant, Record>,
+    /// The match being played, if playing matches
+    pub rps_match: Option<Match>,
+    pub rps_strategy: Strategy,
+    /// How each strategy has done against the player
+    pub rps_strategy_records: HashMap<Strategy, Record>,
+    /// The player's moves in the current variant, which the strategies learn from
+    pub rps_history: Vec<Move>,
+    
+    // Number Guessing
+    pub guess_target: i32,
+    pub guess_attempts: i32,
+    pub guess_message: String,
+    pub guess_game_over: bool,
+    pub guess_won: bool,
+    pub guess_input: String,
+    pub guess_difficulty: Difficulty,
+    pub guess_rules: GuessRules,
+    /// Lowest number the target can still be, going by the feedback so far
+    pub guess_low: i32,
+    /// Highest number the target can still be
+    pub guess_high: i32,
+    /// Guesses that earlier feedback had already ruled out
+    pub guess_wasted: i32,
+    /// Set when the last guess was wasted
+    pub guess_warning: Option<String>,
+    pub guess_custom_min: String,
+    pub guess_custom_max: String,
+    pub guess_custom_attempts: String,
+    
+    // Memory Cards
+    pub memory_cards: Vec<(String, bool, bool)>, // (emoji, revealed, matched)
+    pub memory_moves: i32,
+    pub memory_matches: i32,
+    pub memory_first_card: Option<usize>,
+    pub memory_game_over: bool,
+    pub memory_second_card: Option<usize>,
+    pub memory_showing_pair: bool,
+}
+
+impl GameState {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = SeededRandom::new(seed);
+        let guess_rules = GuessRules::default();
+        GameState {
+            seed,
+            seed_input: seed.to_string(),
+            guess_target: rng.range(guess_rules.min, guess_rules.max),
+            rng,
+            rps_variant: Variant::Classic,
+            rps_rounds: Vec::new(),
+            rps_scores: HashMap::new(),
+            rps_match: None,
+            rps_strategy: Strategy::Random,
+            rps_strategy_records: HashMap::new(),
+            rps_history: Vec::new(),
+            guess_attempts: 0,
+            guess_message: Self::guess_prompt(&guess_rules),
+            guess_game_over: false,
+            guess_won: false,
+            guess_input: String::new(),
+            guess_difficulty: Difficulty::Normal,
+            guess_rules,
+            guess_low: guess_rules.min,
+            guess_high: guess_rules.max,
+            guess_wasted: 0,
+            guess_warning: None,
+            guess_custom_min: guess_rules.min.to_string(),
+            guess_custom_max: guess_rules.max.to_string(),
+            guess_custom_attempts: guess_rules.max_attempts.map(|limit| limit.to_string()).unwrap_or_default(),
+            memory_cards: Vec::new(),
+            memory_moves: 0,
+            memory_matches: 0,
+            memory_first_card: None,
+            memory_game_over: false,
+            memory_second_card: None,
+            memory_showing_pair: false,
+        }
+    }
+    
+    /// Start every game over from `seed`, keeping how each one is set up: the RPS variant,
+    /// opponent and match length, and the guessing difficulty and rules
+    pub fn reseed(&mut self, seed: u64) {
+        let previous = std::mem::replace(self, GameState::new(seed));
+        self.rps_variant = previous.rps_variant;
+        self.rps_strategy = previous.rps_strategy;
+        self.rps_match = previous.rps_match.map(|current| Match::new(current.best_of));
+        self.guess_difficulty = previous.guess_difficulty;
+        self.guess_rules = previous.guess_rules;
+        self.guess_custom_min = previous.guess_custom_min;
+        self.guess_custom_max = previous.guess_custom_max;
+        self.guess_custom_attempts = previous.guess_custom_attempts;
+
+        // Drawn again so the target only depends on the seed and the rules
+        self.rng = SeededRandom::new(seed);
+        self.reset_guess_game();
+    }
+    
+    /// A memory game has been started and cards have been turned, but it isn't won yet
+    pub fn memory_in_progress(&self) -> bool {
+        !self.memory_cards.is_empty()
+            && !self.memory_game_over
+            && (self.memory_moves > 0 || self.memory_first_card.is_some())
+    }
+    
+    /// The player's record in the variant being played
+    pub fn rps_score(&self) -> Record {
+        self.rps_scores.get(&self.rps_variant).copied().unwrap_or_default()
+    }
+    
+    /// The latest round, if it was played in the current variant
+    pub fn rps_last_round(&self) -> Option<&Round> {
+        self.rps_rounds.last().filter(|round| round.variant == self.rps_variant)
+    }
+    
+    /// How the active strategy has done against the player
+    pub fn rps_strategy_record(&self) -> Record {
+        self.rps_strategy_records.get(&self.rps_strategy).copied().unwrap_or_default()
+    }
+    
+    fn guess_prompt(rules: &GuessRules) -> String {
+        format!("Guess a number between {} and {}!", rules.min, rules.max)
+    }
+    
+    /// Guesses left before the game is lost, if there is a limit
+    pub fn guesses_left(&self) -> Option<i32> {
+        self.guess_rules.max_attempts.map(|limit| (limit - self.guess_attempts).max(0))
+    }
+    
+    fn start_guess_game(&mut self, platform: &mut dyn Platform) {
+        platform.log(&format!("Starting new guess game: {}", self.guess_rules.describe()));
+        self.reset_guess_game();
+    }
+    
+    fn reset_guess_game(&mut self) {
+        self.guess_target = self.rng.range(self.guess_rules.min, self.guess_rules.max);
+        self.guess_attempts = 0;
+        self.guess_message = Self::guess_prompt(&self.guess_rules);
+        self.guess_game_over = false;
+        self.guess_won = false;
+        self.guess_low = self.guess_rules.min;
+        self.guess_high = self.guess_rules.max;
+        self.guess_wasted = 0;
+        self.guess_warning = None;
+        self.guess_input.clear();
+    }
+    
+    /// How the finished game compares to binary search
+    pub fn guess_analysis(&self) -> Option<String> {
+        if !self.guess_game_over {
+            return None;
+        }
+        let optimal = self.guess_rules.optimal_guesses();
+        let mut analysis = format!(
+            "Binary search always finds a number from {} to {} within {} guesses; you took {}.",
+            self.guess_rules.min, self.guess_rules.max, optimal, self.guess_attempts
+        );
+        if self.guess_wasted > 0 {
+            analysis.push_str(&format!(" {} of them were already ruled out.", self.guess_wasted));
+        }
+        Some(analysis)
+    }
+    
+    fn make_guess(&mut self, guess: i32, platform: &mut dyn Platform) {
+        if self.guess_game_over {
+            return;
+        }
+        platform.log(&format!("Player guessed: {}", guess));
+        self.guess_attempts += 1;
+        self.guess_input.clear();
+        self.guess_warning = if guess < self.guess_low {
+            Some(format!("⚠️ Wasted guess: you already knew it was at least {}", self.guess_low))
+        } else if guess > self.guess_high {
+            Some(format!("⚠️ Wasted guess: you already knew it was at most {}", self.guess_high))
+        } else {
+            None
+        };
+        if self.guess_warning.is_some() {
+            self.guess_wasted += 1;
+        }
+        
+        if guess == self.guess_target {
+            self.guess_message = format!("🎉 Correct! You found {} in {} attempts!", self.guess_target, self.guess_attempts);
+            self.guess_game_over = true;
+            self.guess_won = true;
+            return;
+        }
+        if guess < self.guess_target {
+            self.guess_low = self.guess_low.max(guess + 1);
+            self.guess_message = "📈 Too low! Try a higher number.".to_string();
+        } else {
+            self.guess_high = self.guess_high.min(guess - 1);
+            self.guess_message = "📉 Too high! Try a lower number.".to_string();
+        }
+        if self.guesses_left() == Some(0) {
+            self.guess_message = format!("💥 Out of guesses! The number was {}.", self.guess_target);
+            self.guess_game_over = true;
+        }
+    }
+    
+    fn initialize_memory_cards(random: &mut dyn Random) -> Vec<(String, bool, bool)> {
+        let emojis = ["🐶", "🐱", "🐭", "🐹", "🐰", "🦊", "🐻", "🐼"];
+        let mut cards = Vec::new();
+        
+        // Add each emoji twice for pairs
+        for emoji in &emojis {
+            cards.push((emoji.to_string(), false, false));
+            cards.push((emoji.to_string(), false, false));
+        }
+        
+        rng::shuffle(&mut cards, random);
+        
+        cards
+    }
+    
+    pub fn update(&mut self, msg: GameMsg, platform: &mut dyn Platform) {
+        match msg {
+            GameMsg::RockPaperScissorsPlay(player_choice) => {
+                if self.rps_match.is_some_and(|current| current.is_over()) {
+                    platform.log("The match is over, start a new one to keep playing");
+                    return;
+                }
+                platform.log(&format!("Player chose: {}", player_choice.name()));
+                let computer_choice = self.rps_strategy.choose(self.rps_variant, &self.rps_history, &mut self.rng);
+                let result = self.rps_variant.outcome(player_choice, computer_choice);
+                self.rps_history.push(player_choice);
+                self.rps_strategy_records.entry(self.rps_strategy).or_default().add(result.reversed());
+                
+                self.rps_scores.entry(self.rps_variant).or_default().add(result);
+                if let Some(current) = &mut self.rps_match {
+                    current.record.add(result);
+                    if let Some(winner) = current.winner() {
+                        platform.log(&format!("Match over: {}", winner.message()));
+                    }
+                }
+                self.rps_rounds.push(Round {
+                    variant: self.rps_variant,
+                    player: player_choice,
+                    computer: computer_choice,
+                    outcome: result,
+                });
+            },
+            GameMsg::SelectRpsVariant(variant) => {
+                platform.log(&format!("Playing {}", variant.label()));
+                self.rps_variant = variant;
+                self.rps_history.clear();
+                // A match is played in one variant
+                self.rps_match = self.rps_match.map(|current| Match::new(current.best_of));
+            },
+            GameMsg::SelectRpsStrategy(strategy) => {
+                platform.log(&format!("Computer now plays {}", strategy.label()));
+                self.rps_strategy = strategy;
+            },
+            GameMsg::SelectRpsMatchLength(best_of) => {
+                platform.log(&format!("Match length: {:?}", best_of));
+                self.rps_match = best_of.map(Match::new);
+            },
+            GameMsg::NewRpsMatch => {
+                self.rps_match = self.rps_match.map(|current| Match::new(current.best_of));
+            },
+            GameMsg::ResetRps => {
+                platform.log("Resetting rock paper scissors");
+                self.rps_rounds.clear();
+                self.rps_scores.clear();
+                self.rps_history.clear();
+                self.rps_strategy_records.clear();
+                self.rps_match = self.rps_match.map(|current| Match::new(current.best_of));
+            },
+            GameMsg::GuessInputChanged(value) => {
+                self.guess_input = value;
+            },
+            GameMsg::SubmitGuess => {
+                let rules = self.guess_rules;
+                if let Ok(num) = self.guess_input.trim().parse::<i32>() {
+                    if rules.contains(num) {
+                        self.make_guess(num, platform);
+                    } else {
+                        self.guess_input.clear();
+                        self.guess_message = format!("Please enter a number between {} and {}!", rules.min, rules.max);
+                    }
+                } else {
+                    self.guess_input.clear();
+                    self.guess_message = "Please enter a valid number!".to_string();
+                }
+            },
+            GameMsg::GuessNumber(guess) => {
+                self.make_guess(guess, platform);
+            },
+            GameMsg::NewGuessGame => {
+                self.start_guess_game(platform);
+            },
+            GameMsg::SelectGuessDifficulty(difficulty) => {
+                self.guess_difficulty = difficulty;
+                // Custom rules only take effect once applied
+                if let Some(rules) = difficulty.rules() {
+                    self.guess_rules = rules;
+                    self.start_guess_game(platform);
+                }
+            },
+            GameMsg::GuessCustomMinChanged(value) => {
+                self.guess_custom_min = value;
+            },
+            GameMsg::GuessCustomMaxChanged(value) => {
+                self.guess_custom_max = value;
+            },
+            GameMsg::GuessCustomAttemptsChanged(value) => {
+                self.guess_custom_attempts = value;
+            },
+            GameMsg::ApplyGuessCustom => {
+                match GuessRules::parse(&self.guess_custom_min, &self.guess_custom_max, &self.guess_custom_attempts) {
+                    Ok(rules) => {
+                        self.guess_rules = rules;
+                        self.start_guess_game(platform);
+                    },
+                    Err(error) => self.guess_message = error,
+                }
+            },
+            GameMsg::StartMemoryGame => {
+                platform.log("Starting memory game");
+                self.memory_cards = Self::initialize_memory_cards(&mut self.rng);
+                self.memory_moves = 0;
+                self.memory_matches = 0;
+                self.memory_first_card = None;
+                self.memory_game_over = false;
+                self.memory_second_card = None;
+                self.memory_showing_pair = false;
+            },
+            GameMsg::MemoryCardClick(index) => {
+                platform.log(&format!("Memory card {} clicked", index));
+                
+                // Don't allow clicks if card is already revealed/matched
+                if self.memory_cards[index].1 || self.memory_cards[index].2 {
+                    return;
+                }
+                
+                // If we're showing a non-matching pair, hide them first
+                if self.memory_showing_pair {
+                    if let (Some(first_index), Some(second_index)) = (self.memory_first_card, self.memory_second_card) {
+                        if !self.memory_cards[first_index].2 && !self.memory_cards[second_index].2 {
+                            self.memory_cards[first_index].1 = false;
+                            self.memory_cards[second_index].1 = false;
+                        }
+                    }
+                    self.memory_first_card = None;
+                    self.memory_second_card = None;
+                    self.memory_showing_pair = false;
+                }
+                
+                // Reveal the clicked card
+                self.memory_cards[index].1 = true;
+                
+                match self.memory_first_card {
+                    None => {
+                        // First card of the pair
+                        self.memory_first_card = Some(index);
+                        platform.log(&format!("First card selected: {}", index));
+                    },
+                    Some(first_index) => {
+                        // Second card of the pair
+                        self.memory_second_card = Some(index);
+                        self.memory_moves += 1;
+                        self.memory_showing_pair = true;
+                        
+                        platform.log(&format!("Second card selected: {}, checking match...", index));
+                        
+                        if self.memory_cards[first_index].0 == self.memory_cards[index].0 {
+                            // Match found!
+                            platform.log("Match found!");
+                            self.memory_cards[first_index].2 = true;
+                            self.memory_cards[index].2 = true;
+                            self.memory_matches += 1;
+                            
+                            // Reset for next turn
+                            self.memory_first_card = None;
+                            self.memory_second_card = None;
+                            self.memory_showing_pair = false;
+                            
+                            // Check if game is complete
+                            if self.memory_matches >= 8 {
+                                self.memory_game_over = true;
+                                platform.log("Game completed!");
+                            }
+                        } else {
+                            // No match - cards will be hidden when next card is clicked
+                            platform.log("No match, cards will be hidden shortly");
+                        }
+                    }
+                }
+            },
+            GameMsg::HideMemoryCards => {
+                platform.log("Hiding non-matching cards");
+                if let (Some(first_index), Some(second_index)) = (self.memory_first_card, self.memory_second_card) {
+                    if !self.memory_cards[first_index].2 && !self.memory_cards[second_index].2 {
+                        self.memory_cards[first_index].1 = false;
+                        self.memory_cards[second_index].1 = false;
+                    }
+                }
+                
+                self.memory_first_card = None;
+                self.memory_second_card = None;
+                self.memory_showing_pair = false;
+            },
+            GameMsg::SeedInputChanged(value) => {
+                self.seed_input = value;
+            },
+            GameMsg::ApplySeed => match self.seed_input.trim().parse::<u64>() {
+                Ok(seed) => {
+                    platform.log(&format!("Restarting games with seed {}", seed));
+                    self.reseed(seed);
+                },
+                Err(_) => self.seed_input = self.seed.to_string(),
+            },
+            GameMsg::NewSeed => {
+                let seed = rng::fresh_seed(platform);
+                platform.log(&format!("Restarting games with seed {}", seed));
+                self.reseed(seed);
+            },
+            GameMsg::ResetMemoryGame => {
+                platform.log("Resetting memory game");
+                self.memory_cards.clear();
+                self.memory_moves = 0;
+                self.memory_matches = 0;
+                self.memory_first_card = None;
+                self.memory_game_over = false;
+                self.memory_second_card = None;
+                self.memory_showing_pair = false;
+            },
+        }
+    }
+}
+
+/// The seed a game URL asks for, if it has a valid one
+pub fn seed_from_query(params: &Query) -> Option<u64> {
+    params.get(SEED_PARAM)?.trim().parse().ok()
+}
+
+/// The number guessing difficulty a game URL asks for, if it names one
+pub fn difficulty_from_query(params: &Query) -> Option<Difficulty> {
+    let slug = params.get(DIFFICULTY_PARAM)?;
+    Difficulty::ALL.into_iter().find(|difficulty| difficulty.slug() == slug.trim())
+}
@@ -0,0 +1,121 @@
+/// Widest custom range, so the number input and quick picks stay usable
+pub const MAX_RANGE_SIZE: i32 = 1_000_000;
+
+/// Most guesses a custom game can allow
+pub const MAX_ATTEMPT_LIMIT: i32 = 100;
+
+/// What a number guessing game is played with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GuessRules {
+    /// Smallest possible target
+    pub min: i32,
+    /// Largest possible target
+    pub max: i32,
+    /// Guesses allowed before the game is lost; `None` for as many as it takes
+    pub max_attempts: Option<i32>,
+}
+
+impl Default for GuessRules {
+    /// The Normal preset
+    fn default() -> Self {
+        GuessRules { min: 1, max: 100, max_attempts: Some(10) }
+    }
+}
+
+impl GuessRules {
+    /// Check a custom game typed into the settings, where a blank or `0` attempt limit means none
+    pub fn parse(min: &str, max: &str, max_attempts: &str) -> Result<Self, String> {
+        let min = min.trim().parse::<i32>().map_err(|_| "The lowest number has to be a whole number".to_string())?;
+        let max = max.trim().parse::<i32>().map_err(|_| "The highest number has to be a whole number".to_string())?;
+        if max <= min {
+            return Err("The highest number has to be above the lowest".to_string());
+        }
+        if max as i64 - min as i64 >= MAX_RANGE_SIZE as i64 {
+            return Err(format!("Ranges can hold at most {} numbers", MAX_RANGE_SIZE));
+        }
+        let max_attempts = match max_attempts.trim() {
+            "" | "0" => None,
+            value => match value.parse::<i32>() {
+                Ok(limit) if (1..=MAX_ATTEMPT_LIMIT).contains(&limit) => Some(limit),
+                _ => return Err(format!("The guess limit has to be between 1 and {}, or blank", MAX_ATTEMPT_LIMIT)),
+            },
+        };
+        Ok(GuessRules { min, max, max_attempts })
+    }
+
+    /// Numbers the target can be
+    pub fn size(&self) -> u32 {
+        (self.max as i64 - self.min as i64 + 1) as u32
+    }
+
+    /// Guesses binary search needs in the worst case, ⌈log₂(size + 1)⌉: each guess either
+    /// hits or halves what's left
+    pub fn optimal_guesses(&self) -> i32 {
+        (u32::BITS - self.size().leading_zeros()) as i32
+    }
+
+    pub fn contains(&self, guess: i32) -> bool {
+        (self.min..=self.max).contains(&guess)
+    }
+
+    pub fn describe(&self) -> String {
+        match self.max_attempts {
+            Some(limit) => format!("{} to {}, {} guesses", self.min, self.max, limit),
+            None => format!("{} to {}, unlimited guesses", self.min, self.max),
+        }
+    }
+}
+
+/// Number guessing presets, or the player's own rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Custom,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Custom];
+
+    /// Rules of a preset; `None` for `Custom`, which takes them from the settings
+    pub fn rules(&self) -> Option<GuessRules> {
+        match self {
+            Difficulty::Easy => Some(GuessRules { min: 1, max: 50, max_attempts: None }),
+            Difficulty::Normal => Some(GuessRules::default()),
+            Difficulty::Hard => Some(GuessRules { min: 1, max: 1000, max_attempts: Some(10) }),
+            Difficulty::Custom => None,
+        }
+    }
+
+    /// Used as the option value in the picker
+    pub fn slug(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Custom => "custom",
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Self {
+        Self::ALL.into_iter().find(|difficulty| difficulty.slug() == slug).unwrap_or(Difficulty::Normal)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Custom => "Custom",
+        }
+    }
+}
+
+/// Up to five guesses spread evenly over `low..=high`: both ends, the middle and the quarters
+pub fn quick_picks(low: i32, high: i32) -> Vec<i32> {
+    let span = high as i64 - low as i64;
+    let mut picks: Vec<i32> = (0..=4).map(|quarter| (low as i64 + span * quarter / 4) as i32).collect();
+    picks.dedup();
+    picks
+}
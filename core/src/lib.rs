@@ -0,0 +1,14 @@
+//! Everything in the demo app that doesn't need a browser: routes, the counter, cat batch
+//! bookkeeping and the games. Randomness, time and logging come in through the traits in
+//! [`platform`], so all of it runs and is tested natively with plain `cargo test`.
+
+pub mod cat_batches;
+pub mod cats;
+pub mod counter;
+pub mod games;
+pub mod guessing;
+pub mod platform;
+pub mod rng;
+pub mod routes;
+pub mod rps;
+pub mod strategies;
@@ -0,0 +1,28 @@
+/// Source of random numbers for the games
+pub trait Random {
+    /// A number in `0.0..1.0`
+    fn next_f64(&mut self) -> f64;
+
+    /// A number in `min..=max`
+    fn range(&mut self, min: i32, max: i32) -> i32 {
+        let span = (max - min + 1) as f64;
+        (min + (self.next_f64() * span) as i32).min(max)
+    }
+}
+
+/// Wall clock time
+pub trait Clock {
+    /// Milliseconds since the epoch
+    fn now_ms(&self) -> f64;
+}
+
+/// Where the core writes what it is doing, for debugging
+pub trait Log {
+    fn log(&self, message: &str);
+}
+
+/// Everything the core needs from whatever it runs on: the browser in the app,
+/// scripted stand-ins in tests.
+pub trait Platform: Random + Clock + Log {}
+
+impl<T: Random + Clock + Log> Platform for T {}
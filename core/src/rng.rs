@@ -0,0 +1,45 @@
+use crate::platform::Random;
+
+/// Largest seed handed out by `fresh_seed`, short enough to read off the screen and type back in
+const MAX_FRESH_SEED: i32 = 999_999;
+
+/// Pseudo-random numbers from a seed (SplitMix64): the same seed always gives the same
+/// sequence, on every platform
+#[derive(Debug, Clone)]
+pub struct SeededRandom {
+    state: u64,
+}
+
+impl SeededRandom {
+    pub fn new(seed: u64) -> Self {
+        SeededRandom { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl Random for SeededRandom {
+    fn next_f64(&mut self) -> f64 {
+        // The top 53 bits fill an f64 mantissa exactly
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// A new seed for when none was given
+pub fn fresh_seed(random: &mut dyn Random) -> u64 {
+    random.range(0, MAX_FRESH_SEED) as u64
+}
+
+/// Fisher–Yates: every order of `items` is equally likely
+pub fn shuffle<T>(items: &mut [T], random: &mut dyn Random) {
+    for i in (1..items.len()).rev() {
+        let j = random.range(0, i as i32) as usize;
+        items.swap(i, j);
+    }
+}
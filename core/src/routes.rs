@@ -0,0 +1,232 @@
+use std::fmt;
+
+use crate::games::GameKind;
+
+/// Decoded `key=value` pairs of a URL query string, in their original order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query(Vec<(String, String)>);
+
+impl Query {
+    pub fn from_pairs(pairs: Vec<(String, String)>) -> Self {
+        Query(pairs)
+    }
+
+    /// First value of `key`
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.0.iter().find(|(k, _)| k == key).map(|(_, value)| value.clone())
+    }
+
+    /// Set `key` to `value` in place of its first value, dropping any others, or add it at the end
+    pub fn set(&mut self, key: &str, value: String) {
+        let mut value = Some(value);
+        self.0.retain_mut(|(k, v)| {
+            if k != key {
+                return true;
+            }
+            match value.take() {
+                Some(new) => {
+                    *v = new;
+                    true
+                }
+                None => false,
+            }
+        });
+        if let Some(value) = value {
+            self.0.push((key.to_string(), value));
+        }
+    }
+
+    pub fn parse(query: &str) -> Self {
+        let pairs = query
+            .trim_start_matches('?')
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| match pair.split_once('=') {
+                Some((key, value)) => (percent_decode(key), percent_decode(value)),
+                None => (percent_decode(pair), String::new()),
+            })
+            .collect();
+        Query(pairs)
+    }
+}
+
+impl fmt::Display for Query {
+    /// Renders with a leading `?`, or nothing at all when empty
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (key, value)) in self.0.iter().enumerate() {
+            let separator = if i == 0 { '?' } else { '&' };
+            write!(f, "{}{}={}", separator, percent_encode(key), percent_encode(value))?;
+        }
+        Ok(())
+    }
+}
+
+/// Escape everything but unreserved characters, for use in a path segment or query
+pub fn percent_encode(input: &str) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = match bytes.get(i + 1..i + 3) {
+            Some(&[high, low]) if bytes[i] == b'%' => hex_value(high).zip(hex_value(low)),
+            _ => None,
+        };
+        match (bytes[i], escaped) {
+            (_, Some((high, low))) => {
+                decoded.push(high << 4 | low);
+                i += 3;
+            }
+            (b'+', None) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, None) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn hex_value(digit: u8) -> Option<u8> {
+    (digit as char).to_digit(16).map(|value| value as u8)
+}
+
+/// A page of the app, parsed from an app-relative URL such as `/games/number-guessing?difficulty=hard`.
+///
+/// Grammar:
+/// - `/` → `Home`
+/// - `/counter` → `Counter`
+/// - `/cats` → `Cats`, `/cats/<id>` → `Cat(id)` naming one cat the way its source links it
+/// - `/games` → `Games { params }`, `/games/<slug>` → `Game { kind, params }`, both with the query
+///   string as params
+/// - `/favorites` → `Favorites`
+/// - `/settings` → `Settings`
+/// - `/about` → `About`
+/// - anything else → `NotFound` carrying the requested URL
+#[derive(Debug, Clone, PartialEq)]
+pub enum Route {
+    Home,
+    Counter,
+    Cats,
+    Cat(String),
+    Games { params: Query },
+    Game { kind: GameKind, params: Query },
+    Favorites,
+    Settings,
+    About,
+    NotFound(String),
+}
+
+impl Route {
+    /// Parse an app-relative URL; a trailing slash on the path is ignored.
+    pub fn from_url(url: &str) -> Self {
+        let (path, query) = match url.split_once('?') {
+            Some((path, query)) => (path, Query::parse(query)),
+            None => (url, Query::default()),
+        };
+        let segments: Vec<&str> = path.split('/').filter(|segment| !segment.is_empty()).collect();
+
+        match segments.as_slice() {
+            [] => Route::Home,
+            ["counter"] => Route::Counter,
+            ["cats"] => Route::Cats,
+            ["cats", id] => Route::Cat(percent_decode(id)),
+            ["games"] => Route::Games { params: query },
+            ["games", slug] => match GameKind::from_slug(slug) {
+                Some(kind) => Route::Game { kind, params: query },
+                None => Route::NotFound(url.to_string()),
+            },
+            ["favorites"] => Route::Favorites,
+            ["settings"] => Route::Settings,
+            ["about"] => Route::About,
+            _ => Route::NotFound(url.to_string()),
+        }
+    }
+
+    pub fn to_url(&self) -> String {
+        match self {
+            Route::Home => "/".to_string(),
+            Route::Counter => "/counter".to_string(),
+            Route::Cats => "/cats".to_string(),
+            Route::Cat(id) => format!("/cats/{}", percent_encode(id)),
+            Route::Games { params } => format!("/games{}", params),
+            Route::Game { kind, params } => format!("/games/{}{}", kind.slug(), params),
+            Route::Favorites => "/favorites".to_string(),
+            Route::Settings => "/settings".to_string(),
+            Route::About => "/about".to_string(),
+            Route::NotFound(url) => url.clone(),
+        }
+    }
+
+    /// The top-level page this route belongs to, used for navigation highlighting
+    pub fn section(&self) -> Route {
+        match self {
+            Route::Cat(_) => Route::Cats,
+            Route::Games { .. } | Route::Game { .. } => Route::games(),
+            route => route.clone(),
+        }
+    }
+
+    pub fn title(&self) -> String {
+        let page = match self {
+            Route::Home => return APP_NAME.to_string(),
+            Route::Counter => "Counter".to_string(),
+            Route::Cats => "Cats".to_string(),
+            Route::Cat(_) => "Shared Cat".to_string(),
+            Route::Games { .. } => "Games".to_string(),
+            Route::Game { kind, .. } => kind.title().to_string(),
+            Route::Favorites => "Favorite Cats".to_string(),
+            Route::Settings => "Settings".to_string(),
+            Route::About => "About".to_string(),
+            Route::NotFound(_) => "Page Not Found".to_string(),
+        };
+        format!("{} | {}", page, APP_NAME)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Route::Home => "A Rust WebAssembly application with routing, built with Sauron.",
+            Route::Counter => "An interactive counter with increment, doubling and halving.",
+            Route::Cats | Route::Cat(_) => "An infinite, preloaded browser of random cat pictures.",
+            Route::Games { .. } => "Mini games: Rock Paper Scissors, Number Guessing and Memory Cards.",
+            Route::Game { kind, .. } => kind.description(),
+            Route::Favorites => "The cats you kept, saved in this browser.",
+            Route::Settings => "How the cat browser preloads images.",
+            Route::About => "About this Rust and WebAssembly demo app.",
+            Route::NotFound(_) => "The requested page does not exist.",
+        }
+    }
+
+    /// The games overview without params
+    pub fn games() -> Self {
+        Route::Games { params: Query::default() }
+    }
+
+    /// Params of the games pages, which the games are set up from
+    pub fn game_params(&self) -> Option<&Query> {
+        match self {
+            Route::Games { params } | Route::Game { params, .. } => Some(params),
+            _ => None,
+        }
+    }
+
+    /// Parse the fragment of a hash-mode URL, e.g. `#/cats/3`
+    pub fn from_hash(hash: &str) -> Self {
+        Self::from_url(hash.trim_start_matches('#'))
+    }
+}
+
+const APP_NAME: &str = "Sauron Demo";
@@ -0,0 +1,264 @@
+use std::fmt;
+
+/// A hand in Rock Paper Scissors or one of its variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Move {
+    Rock,
+    Paper,
+    Scissors,
+    Lizard,
+    Spock,
+    /// The 0-based place of a move in a cyclic variant
+    Numbered(u8),
+}
+
+impl Move {
+    pub fn name(&self) -> String {
+        match self {
+            Move::Rock => "Rock".to_string(),
+            Move::Paper => "Paper".to_string(),
+            Move::Scissors => "Scissors".to_string(),
+            Move::Lizard => "Lizard".to_string(),
+            Move::Spock => "Spock".to_string(),
+            Move::Numbered(index) => format!("Move {}", index + 1),
+        }
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Move::Rock => "🪨",
+            Move::Paper => "📄",
+            Move::Scissors => "✂️",
+            Move::Lizard => "🦎",
+            Move::Spock => "🖖",
+            Move::Numbered(_) => "🔢",
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    /// Emoji and name, e.g. `🪨 Rock`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.emoji(), self.name())
+    }
+}
+
+/// How a round went, for the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Tie,
+}
+
+impl Outcome {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Outcome::Win => "You win!",
+            Outcome::Loss => "Computer wins!",
+            Outcome::Tie => "It's a tie!",
+        }
+    }
+
+    /// The same round seen from the other side
+    pub fn reversed(&self) -> Outcome {
+        match self {
+            Outcome::Win => Outcome::Loss,
+            Outcome::Loss => Outcome::Win,
+            Outcome::Tie => Outcome::Tie,
+        }
+    }
+}
+
+/// Rounds won, lost and tied
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Record {
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+}
+
+impl Record {
+    pub fn add(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Win => self.wins += 1,
+            Outcome::Loss => self.losses += 1,
+            Outcome::Tie => self.ties += 1,
+        }
+    }
+
+    pub fn rounds(&self) -> u32 {
+        self.wins + self.losses + self.ties
+    }
+
+    /// Share of rounds won, once there has been one
+    pub fn win_rate(&self) -> Option<f64> {
+        (self.rounds() > 0).then(|| self.wins as f64 / self.rounds() as f64)
+    }
+}
+
+/// One round as it was played
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Round {
+    pub variant: Variant,
+    pub player: Move,
+    pub computer: Move,
+    /// For the player
+    pub outcome: Outcome,
+}
+
+/// A best-of match: the first to win a majority of `best_of` rounds takes it, and ties
+/// don't count towards the rounds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Match {
+    pub best_of: u32,
+    /// The player's record in this match
+    pub record: Record,
+}
+
+impl Match {
+    pub const LENGTHS: [u32; 3] = [3, 5, 7];
+
+    pub fn new(best_of: u32) -> Self {
+        Match { best_of, record: Record::default() }
+    }
+
+    pub fn wins_needed(&self) -> u32 {
+        self.best_of / 2 + 1
+    }
+
+    /// `Win` if the player took the match, `Loss` if the computer did
+    pub fn winner(&self) -> Option<Outcome> {
+        if self.record.wins >= self.wins_needed() {
+            Some(Outcome::Win)
+        } else if self.record.losses >= self.wins_needed() {
+            Some(Outcome::Loss)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.winner().is_some()
+    }
+}
+
+/// Who beats whom in the named variants: `(winner, loser, verb)`
+const RULES: &[(Move, Move, &str)] = &[
+    (Move::Rock, Move::Scissors, "crushes"),
+    (Move::Rock, Move::Lizard, "crushes"),
+    (Move::Paper, Move::Rock, "covers"),
+    (Move::Paper, Move::Spock, "disproves"),
+    (Move::Scissors, Move::Paper, "cuts"),
+    (Move::Scissors, Move::Lizard, "decapitates"),
+    (Move::Lizard, Move::Paper, "eats"),
+    (Move::Lizard, Move::Spock, "poisons"),
+    (Move::Spock, Move::Scissors, "smashes"),
+    (Move::Spock, Move::Rock, "vaporizes"),
+];
+
+/// Which moves are in play and how they beat each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Variant {
+    Classic,
+    LizardSpock,
+    /// An odd number of numbered moves, each beating the half of the others just before it
+    Cyclic(u8),
+}
+
+impl Variant {
+    pub const MIN_CYCLIC_MOVES: u8 = 3;
+    pub const MAX_CYCLIC_MOVES: u8 = 15;
+
+    /// A cyclic variant with `moves` moves, made odd and kept in range so every move
+    /// beats exactly as many moves as it loses to
+    pub fn cyclic(moves: u8) -> Self {
+        Variant::Cyclic(moves.clamp(Self::MIN_CYCLIC_MOVES, Self::MAX_CYCLIC_MOVES) | 1)
+    }
+
+    /// The variants offered in the picker: the named ones, then every cyclic variant
+    /// with more moves than they have
+    pub fn choices() -> Vec<Variant> {
+        let cyclic = (7..=Self::MAX_CYCLIC_MOVES).step_by(2).map(Variant::Cyclic);
+        [Variant::Classic, Variant::LizardSpock].into_iter().chain(cyclic).collect()
+    }
+
+    /// Used as the option value in the picker
+    pub fn slug(&self) -> String {
+        match self {
+            Variant::Classic => "classic".to_string(),
+            Variant::LizardSpock => "lizard-spock".to_string(),
+            Variant::Cyclic(count) => format!("cyclic-{}", count),
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Self {
+        match slug {
+            "lizard-spock" => Variant::LizardSpock,
+            _ => match slug.strip_prefix("cyclic-").and_then(|count| count.parse().ok()) {
+                Some(count) => Variant::cyclic(count),
+                None => Variant::Classic,
+            },
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Variant::Classic => "Rock Paper Scissors".to_string(),
+            Variant::LizardSpock => "Rock Paper Scissors Lizard Spock".to_string(),
+            Variant::Cyclic(count) => format!("Cyclic, {} moves", count),
+        }
+    }
+
+    pub fn moves(&self) -> Vec<Move> {
+        match self {
+            Variant::Classic => vec![Move::Rock, Move::Paper, Move::Scissors],
+            Variant::LizardSpock => vec![Move::Rock, Move::Paper, Move::Scissors, Move::Lizard, Move::Spock],
+            Variant::Cyclic(count) => (0..*count).map(Move::Numbered).collect(),
+        }
+    }
+
+    /// How `winner` beats `loser`, if it does
+    pub fn beats(&self, winner: Move, loser: Move) -> Option<&'static str> {
+        match (self, winner, loser) {
+            (Variant::Cyclic(count), Move::Numbered(winner), Move::Numbered(loser)) => {
+                let distance = (winner as i32 - loser as i32).rem_euclid(*count as i32);
+                (1..=(*count as i32 - 1) / 2).contains(&distance).then_some("beats")
+            }
+            (Variant::Cyclic(_), _, _) => None,
+            (variant, winner, loser) => {
+                let moves = variant.moves();
+                if !moves.contains(&winner) || !moves.contains(&loser) {
+                    return None;
+                }
+                RULES.iter().find(|rule| rule.0 == winner && rule.1 == loser).map(|rule| rule.2)
+            }
+        }
+    }
+
+    /// The moves that beat `target`
+    pub fn counters(&self, target: Move) -> Vec<Move> {
+        self.moves().into_iter().filter(|&candidate| self.beats(candidate, target).is_some()).collect()
+    }
+
+    pub fn outcome(&self, player: Move, computer: Move) -> Outcome {
+        if self.beats(player, computer).is_some() {
+            Outcome::Win
+        } else if self.beats(computer, player).is_some() {
+            Outcome::Loss
+        } else {
+            Outcome::Tie
+        }
+    }
+
+    /// What happened in a round, e.g. `✂️ Scissors cuts 📄 Paper`
+    pub fn describe(&self, player: Move, computer: Move) -> String {
+        if let Some(verb) = self.beats(player, computer) {
+            format!("{} {} {}", player, verb, computer)
+        } else if let Some(verb) = self.beats(computer, player) {
+            format!("{} {} {}", computer, verb, player)
+        } else {
+            format!("Both played {}", player)
+        }
+    }
+}
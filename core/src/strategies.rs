@@ -0,0 +1,121 @@
+use crate::platform::Random;
+use crate::rps::{Move, Record, Variant};
+
+/// Longest context the Markov predictor on offer looks at
+pub const MAX_MARKOV_ORDER: u8 = 3;
+
+/// How the computer picks its Rock Paper Scissors move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    /// Uniformly at random, which can't be beaten or exploited in the long run
+    Random,
+    /// Beat the opponent's most played move
+    Frequency,
+    /// Beat whatever the opponent most often played after their last `n` moves
+    Markov(u8),
+    /// Beat the opponent's previous move, on the bet that they repeat it
+    BeatLast,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 6] = [
+        Strategy::Random,
+        Strategy::Frequency,
+        Strategy::Markov(1),
+        Strategy::Markov(2),
+        Strategy::Markov(MAX_MARKOV_ORDER),
+        Strategy::BeatLast,
+    ];
+
+    /// Used as the option value in the picker
+    pub fn slug(&self) -> String {
+        match self {
+            Strategy::Random => "random".to_string(),
+            Strategy::Frequency => "frequency".to_string(),
+            Strategy::Markov(order) => format!("markov-{}", order),
+            Strategy::BeatLast => "beat-last".to_string(),
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Self {
+        Self::ALL.into_iter().find(|strategy| strategy.slug() == slug).unwrap_or(Strategy::Random)
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Strategy::Random => "Random".to_string(),
+            Strategy::Frequency => "Frequency counter".to_string(),
+            Strategy::Markov(order) => format!("Pattern predictor ({} move{})", order, if *order == 1 { "" } else { "s" }),
+            Strategy::BeatLast => "Beat last move".to_string(),
+        }
+    }
+
+    /// What the opponent is expected to play next, given everything they played so far
+    pub fn predict(&self, opponent_moves: &[Move]) -> Option<Move> {
+        match self {
+            Strategy::Random => None,
+            Strategy::Frequency => most_common(opponent_moves),
+            Strategy::Markov(order) => predict_markov(opponent_moves, *order as usize),
+            Strategy::BeatLast => opponent_moves.last().copied(),
+        }
+    }
+
+    /// This round's move in `variant`: one that beats the prediction, or any move without one
+    pub fn choose(&self, variant: Variant, opponent_moves: &[Move], random: &mut dyn Random) -> Move {
+        let candidates = match self.predict(opponent_moves) {
+            Some(predicted) => variant.counters(predicted),
+            None => Vec::new(),
+        };
+        let candidates = if candidates.is_empty() { variant.moves() } else { candidates };
+        candidates[random.range(0, candidates.len() as i32 - 1) as usize]
+    }
+}
+
+/// The move played most often, ties going to the one played most recently
+fn most_common(moves: &[Move]) -> Option<Move> {
+    let mut counts: Vec<(Move, usize, usize)> = Vec::new(); // (move, count, last index)
+    for (index, played) in moves.iter().enumerate() {
+        match counts.iter_mut().find(|(counted, _, _)| counted == played) {
+            Some(entry) => {
+                entry.1 += 1;
+                entry.2 = index;
+            }
+            None => counts.push((*played, 1, index)),
+        }
+    }
+    counts.into_iter().max_by_key(|&(_, count, last)| (count, last)).map(|(played, _, _)| played)
+}
+
+/// What most often followed the last `order` moves, backing off to shorter contexts
+/// and then to plain frequency when the context hasn't been seen before
+fn predict_markov(moves: &[Move], order: usize) -> Option<Move> {
+    for length in (1..=order.min(moves.len())).rev() {
+        let context = &moves[moves.len() - length..];
+        let followers: Vec<Move> = moves
+            .windows(length + 1)
+            .filter(|window| &window[..length] == context)
+            .map(|window| window[length])
+            .collect();
+        if let Some(predicted) = most_common(&followers) {
+            return Some(predicted);
+        }
+    }
+    most_common(moves)
+}
+
+/// Play `rounds` rounds of `variant` between two strategies, each seeing the other's past
+/// moves; returns `first`'s record
+pub fn play_off(variant: Variant, first: Strategy, second: Strategy, rounds: u32, random: &mut dyn Random) -> Record {
+    let mut first_moves = Vec::new();
+    let mut second_moves = Vec::new();
+    let mut record = Record::default();
+    for _ in 0..rounds {
+        let first_move = first.choose(variant, &second_moves, random);
+        let second_move = second.choose(variant, &first_moves, random);
+        record.add(variant.outcome(first_move, second_move));
+        first_moves.push(first_move);
+        second_moves.push(second_move);
+    }
+    record
+}
+
@@ -0,0 +1,240 @@
+mod common;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use common::TestPlatform;
+use counter_core::cat_batches::{CatBatches, ImageAction};
+use counter_core::cats::{CatQuery, CatSource, CatWindow, FixtureSource, ImageSlot, ImageStatus, LocalSource, PreloadSettings};
+
+fn batches(batch_size: usize, max_concurrent: usize) -> CatBatches {
+    let settings = PreloadSettings { batch_size, look_ahead: 1, max_concurrent, adaptive: false };
+    CatBatches::new(Box::new(FixtureSource::new()), CatWindow { behind: 2, ahead: 20 }, settings)
+}
+
+fn loads_started(actions: &[ImageAction]) -> Vec<ImageSlot> {
+    actions
+        .iter()
+        .filter_map(|action| match action {
+            ImageAction::Load { slot, .. } => Some(*slot),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn first_batch_loads_within_the_concurrency_limit() {
+    let platform = TestPlatform::default();
+    let mut cats = batches(4, 2);
+    cats.start(None, &platform);
+
+    let actions = cats.take_actions();
+    assert_eq!(actions[0], ImageAction::Reset(4));
+    assert_eq!(loads_started(&actions), vec![ImageSlot::Current(0), ImageSlot::Current(1)]);
+    assert!(cats.loading);
+
+    cats.image_loaded(cats.generation, ImageSlot::Current(0), &platform);
+    assert_eq!(loads_started(&cats.take_actions()), vec![ImageSlot::Current(2)]);
+    for index in 1..4 {
+        cats.image_loaded(cats.generation, ImageSlot::Current(index), &platform);
+    }
+    assert!(!cats.loading);
+    assert_eq!(cats.loaded_count, 4);
+}
+
+#[test]
+fn links_bring_back_the_linked_cat() {
+    let platform = TestPlatform::default();
+    let mut cats = batches(3, 6);
+    cats.start(Some("42"), &platform);
+
+    assert_eq!(cats.position(), 0);
+    assert_eq!(cats.urls.len(), 3);
+    assert!(cats.current_url().unwrap().contains("Cat 42"));
+    assert_eq!(cats.current_link_id().as_deref(), Some("42"));
+
+    // The rest of the batch is whatever the source hands out next, without skipping any
+    assert!(cats.show_linked("2"));
+    assert_eq!(cats.current_index, 2);
+    assert!(!cats.show_linked("3"));
+    assert!(!cats.show_linked("not a cat"));
+}
+
+#[test]
+fn links_the_source_cannot_resolve_start_a_fresh_batch() {
+    let platform = TestPlatform::default();
+    let mut cats = batches(3, 6);
+    cats.start(Some("whiskers"), &platform);
+
+    assert!(cats.current_url().unwrap().contains("Cat 1"));
+    assert_eq!(cats.current_link_id().as_deref(), Some("1"));
+}
+
+#[test]
+fn next_batch_joins_once_settled_and_old_cats_are_evicted() {
+    let platform = TestPlatform::default();
+    let mut cats = batches(3, 6);
+    cats.start(None, &platform);
+    for index in 0..3 {
+        cats.image_loaded(cats.generation, ImageSlot::Current(index), &platform);
+    }
+    cats.take_actions();
+
+    // One cat left ahead is within the look-ahead
+    cats.next(&platform);
+    assert!(cats.preloading_next_batch);
+    assert_eq!(cats.next_batch_urls.len(), 3);
+    assert!(cats.take_actions().contains(&ImageAction::PrepareNextBatch(3)));
+
+    for index in 0..3 {
+        cats.image_loaded(cats.generation, ImageSlot::NextBatch(index), &platform);
+    }
+    assert_eq!(cats.take_actions().last(), Some(&ImageAction::CommitNextBatch));
+    assert_eq!(cats.urls.len(), 6);
+
+    for _ in 0..4 {
+        cats.next(&platform);
+    }
+    assert_eq!(cats.position(), 5);
+    assert_eq!(cats.evicted, 3);
+    assert_eq!(cats.current_index, 2);
+    assert!(cats.take_actions().contains(&ImageAction::Evict(1)));
+}
+
+#[test]
+fn failed_images_retry_then_get_replaced_then_given_up_on() {
+    let platform = TestPlatform::default();
+    let mut cats = batches(1, 6);
+    cats.start(None, &platform);
+    let slot = ImageSlot::Current(0);
+    let first_url = cats.urls[0].clone();
+    cats.take_actions();
+
+    cats.image_failed(cats.generation, slot, &platform);
+    assert_eq!(cats.take_actions(), vec![ImageAction::RetryAfter { generation: cats.generation, slot, delay_ms: 500 }]);
+    assert_eq!(cats.image_load(slot).unwrap().status, ImageStatus::Retrying);
+    cats.retry(cats.generation, slot, &platform);
+    assert_eq!(loads_started(&cats.take_actions()), vec![slot]);
+
+    cats.image_failed(cats.generation, slot, &platform);
+    cats.retry(cats.generation, slot, &platform);
+    cats.image_failed(cats.generation, slot, &platform);
+    assert_ne!(cats.urls[0], first_url, "a fresh cat replaces the broken one");
+    cats.take_actions();
+
+    // Every attempt at every replacement fails
+    while cats.loading {
+        cats.image_failed(cats.generation, slot, &platform);
+        cats.retry(cats.generation, slot, &platform);
+    }
+    assert_eq!(cats.image_load(slot).unwrap().status, ImageStatus::Failed);
+    assert!(platform.logged("Giving up"));
+}
+
+#[test]
+fn load_times_come_from_the_clock() {
+    let platform = TestPlatform::default();
+    let mut cats = batches(2, 6);
+    cats.start(None, &platform);
+
+    platform.advance(300.0);
+    cats.image_loaded(cats.generation, ImageSlot::Current(0), &platform);
+    assert_eq!(cats.timer.average_ms(), Some(300.0));
+
+    // The second image took 500ms in all, and the average only moves part of the way there
+    platform.advance(200.0);
+    cats.image_loaded(cats.generation, ImageSlot::Current(1), &platform);
+    assert_eq!(cats.timer.average_ms(), Some(340.0));
+}
+
+#[test]
+fn duplicate_load_callbacks_are_ignored() {
+    let platform = TestPlatform::default();
+    let mut cats = batches(2, 6);
+    cats.start(None, &platform);
+    for index in 0..2 {
+        cats.image_loaded(cats.generation, ImageSlot::Current(index), &platform);
+    }
+    cats.image_loaded(cats.generation, ImageSlot::Current(0), &platform);
+    assert_eq!(cats.loaded_count, 2);
+}
+
+#[test]
+fn callbacks_from_replaced_searches_are_ignored() {
+    let platform = TestPlatform::default();
+    let mut cats = batches(2, 6);
+    cats.start(None, &platform);
+    let stale = cats.generation;
+    cats.image_loaded(stale, ImageSlot::Current(0), &platform);
+
+    assert!(cats.set_query(CatQuery { tag: "orange".to_string(), ..CatQuery::default() }, &platform));
+    assert!(cats.loading);
+    cats.image_loaded(stale, ImageSlot::Current(1), &platform);
+    cats.image_failed(stale, ImageSlot::Current(0), &platform);
+    assert_eq!(cats.loaded_count, 0);
+    assert_eq!(cats.image_load(ImageSlot::Current(0)).unwrap().status, ImageStatus::Loading);
+    assert_eq!(cats.image_load(ImageSlot::Current(1)).unwrap().status, ImageStatus::Loading);
+
+    cats.image_loaded(cats.generation, ImageSlot::Current(1), &platform);
+    assert_eq!(cats.loaded_count, 1);
+}
+
+#[test]
+fn an_empty_source_stops_loading_and_browsing_does_nothing() {
+    let platform = TestPlatform::default();
+    let settings = PreloadSettings { batch_size: 3, look_ahead: 1, max_concurrent: 6, adaptive: false };
+    let mut cats = CatBatches::new(Box::new(LocalSource::new(Vec::new())), CatWindow::default(), settings);
+    cats.start(None, &platform);
+
+    assert!(!cats.loading);
+    assert_eq!(cats.take_actions(), vec![ImageAction::Reset(0)]);
+    cats.next(&platform);
+    cats.show_at(5);
+    cats.prefetch_if_needed(&platform);
+    assert_eq!(cats.current_url(), None);
+    assert!(!cats.preloading_next_batch);
+    assert!(cats.take_actions().is_empty());
+}
+
+/// Hands out one batch and nothing after, counting the failures it is told about
+struct DrySource {
+    batch: Vec<String>,
+    failures: Rc<Cell<u32>>,
+}
+
+impl CatSource for DrySource {
+    fn name(&self) -> &'static str {
+        "dry"
+    }
+
+    fn next_batch(&mut self, _count: usize) -> Vec<String> {
+        std::mem::take(&mut self.batch)
+    }
+
+    fn image_failed(&mut self, _url: &str) {
+        self.failures.set(self.failures.get() + 1);
+    }
+}
+
+#[test]
+fn images_without_a_replacement_are_given_up_on_once() {
+    let platform = TestPlatform::default();
+    let failures = Rc::new(Cell::new(0));
+    let source = DrySource { batch: vec!["cat.jpg".to_string()], failures: failures.clone() };
+    let settings = PreloadSettings { batch_size: 1, look_ahead: 1, max_concurrent: 6, adaptive: false };
+    let mut cats = CatBatches::new(Box::new(source), CatWindow::default(), settings);
+    cats.start(None, &platform);
+    let slot = ImageSlot::Current(0);
+
+    for _ in 0..3 {
+        cats.image_failed(cats.generation, slot, &platform);
+        cats.retry(cats.generation, slot, &platform);
+    }
+    assert_eq!(failures.get(), 3, "each real failure reaches the source once");
+    assert_eq!(cats.image_load(slot).unwrap().status, ImageStatus::Failed);
+    assert!(!cats.loading);
+
+    // Late errors for a batch that has settled change nothing
+    cats.image_failed(cats.generation, slot, &platform);
+    assert_eq!(failures.get(), 3);
+}
@@ -0,0 +1,49 @@
+// Each test crate uses its own subset of these helpers
+#![allow(dead_code)]
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+use counter_core::platform::{Clock, Log, Random};
+
+/// Platform double: scripted random numbers, a clock that only moves when told to,
+/// and a record of everything logged
+#[derive(Default)]
+pub struct TestPlatform {
+    rolls: VecDeque<f64>,
+    now: Cell<f64>,
+    pub logs: RefCell<Vec<String>>,
+}
+
+impl TestPlatform {
+    /// Hands out `rolls` in order, then 0.0 forever
+    pub fn with_rolls(rolls: &[f64]) -> Self {
+        TestPlatform { rolls: rolls.iter().copied().collect(), ..Default::default() }
+    }
+
+    pub fn advance(&self, ms: f64) {
+        self.now.set(self.now.get() + ms);
+    }
+
+    pub fn logged(&self, needle: &str) -> bool {
+        self.logs.borrow().iter().any(|line| line.contains(needle))
+    }
+}
+
+impl Random for TestPlatform {
+    fn next_f64(&mut self) -> f64 {
+        self.rolls.pop_front().unwrap_or(0.0)
+    }
+}
+
+impl Clock for TestPlatform {
+    fn now_ms(&self) -> f64 {
+        self.now.get()
+    }
+}
+
+impl Log for TestPlatform {
+    fn log(&self, message: &str) {
+        self.logs.borrow_mut().push(message.to_string());
+    }
+}
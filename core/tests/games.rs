@@ -0,0 +1,301 @@
+mod common;
+
+use common::TestPlatform;
+use counter_core::games::{difficulty_from_query, seed_from_query, GameMsg, GameState};
+use counter_core::guessing::Difficulty;
+use counter_core::routes::Query;
+use counter_core::rps::{Match, Move, Outcome, Record, Variant};
+use counter_core::strategies::Strategy;
+
+#[test]
+fn rock_paper_scissors_keeps_score_per_variant() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(7);
+    let mut expected = Record::default();
+
+    for _ in 0..30 {
+        games.update(GameMsg::RockPaperScissorsPlay(Move::Rock), &mut platform);
+        let round = *games.rps_last_round().unwrap();
+        assert_eq!(round.player, Move::Rock);
+        assert!(Variant::Classic.moves().contains(&round.computer));
+        assert_eq!(round.outcome, Variant::Classic.outcome(Move::Rock, round.computer));
+        expected.add(round.outcome);
+    }
+    assert_eq!(games.rps_score(), expected);
+    assert!(expected.ties > 0, "ties are counted too");
+    assert_eq!(games.rps_rounds.len(), 30);
+    assert!(platform.logged("Player chose: Rock"));
+
+    games.update(GameMsg::SelectRpsVariant(Variant::LizardSpock), &mut platform);
+    assert_eq!(games.rps_score(), Record::default());
+    assert_eq!(games.rps_last_round(), None);
+    for _ in 0..30 {
+        games.update(GameMsg::RockPaperScissorsPlay(Move::Spock), &mut platform);
+        assert!(Variant::LizardSpock.moves().contains(&games.rps_last_round().unwrap().computer));
+    }
+    games.update(GameMsg::SelectRpsVariant(Variant::Classic), &mut platform);
+    assert_eq!(games.rps_score(), expected, "each variant keeps its own score");
+    assert_eq!(games.rps_rounds.len(), 60, "the history spans variants");
+}
+
+#[test]
+fn matches_end_once_someone_wins_a_majority() {
+    let mut current = Match::new(5);
+    assert_eq!(current.wins_needed(), 3);
+    for outcome in [Outcome::Win, Outcome::Tie, Outcome::Loss, Outcome::Win, Outcome::Tie] {
+        current.record.add(outcome);
+        assert!(!current.is_over());
+    }
+    current.record.add(Outcome::Win);
+    assert_eq!(current.winner(), Some(Outcome::Win));
+
+    let mut lost = Match::new(3);
+    lost.record.add(Outcome::Loss);
+    lost.record.add(Outcome::Loss);
+    assert_eq!(lost.winner(), Some(Outcome::Loss));
+}
+
+#[test]
+fn best_of_matches_stop_play_until_a_new_one() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(7);
+    games.update(GameMsg::SelectRpsStrategy(Strategy::Frequency), &mut platform);
+    games.update(GameMsg::SelectRpsMatchLength(Some(3)), &mut platform);
+
+    // Always playing rock, the frequency counter takes the match once it has seen a rock
+    while !games.rps_match.unwrap().is_over() {
+        games.update(GameMsg::RockPaperScissorsPlay(Move::Rock), &mut platform);
+    }
+    assert_eq!(games.rps_match.unwrap().winner(), Some(Outcome::Loss));
+    let rounds = games.rps_rounds.len();
+    games.update(GameMsg::RockPaperScissorsPlay(Move::Rock), &mut platform);
+    assert_eq!(games.rps_rounds.len(), rounds, "no rounds once the match is over");
+
+    games.update(GameMsg::NewRpsMatch, &mut platform);
+    assert_eq!(games.rps_match, Some(Match::new(3)));
+    games.update(GameMsg::RockPaperScissorsPlay(Move::Rock), &mut platform);
+    assert_eq!(games.rps_rounds.len(), rounds + 1);
+    assert_eq!(games.rps_score().rounds() as usize, rounds + 1, "the scoreboard outlives matches");
+
+    games.update(GameMsg::SelectRpsMatchLength(None), &mut platform);
+    assert_eq!(games.rps_match, None);
+}
+
+#[test]
+fn resetting_clears_scores_history_and_learning() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(7);
+    games.update(GameMsg::SelectRpsMatchLength(Some(7)), &mut platform);
+    for _ in 0..4 {
+        games.update(GameMsg::RockPaperScissorsPlay(Move::Paper), &mut platform);
+    }
+
+    games.update(GameMsg::ResetRps, &mut platform);
+    assert!(games.rps_rounds.is_empty());
+    assert!(games.rps_history.is_empty());
+    assert_eq!(games.rps_score(), Record::default());
+    assert_eq!(games.rps_strategy_record(), Record::default());
+    assert_eq!(games.rps_match, Some(Match::new(7)), "the match length is kept");
+}
+
+#[test]
+fn guesses_get_higher_or_lower_feedback() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(1234);
+    games.guess_target = 42;
+
+    games.update(GameMsg::GuessNumber(10), &mut platform);
+    assert!(games.guess_message.contains("Too low"));
+    games.update(GameMsg::GuessNumber(80), &mut platform);
+    assert!(games.guess_message.contains("Too high"));
+    assert!(!games.guess_game_over);
+
+    games.update(GameMsg::GuessInputChanged(" 42 ".to_string()), &mut platform);
+    games.update(GameMsg::SubmitGuess, &mut platform);
+    assert!(games.guess_game_over);
+    assert_eq!(games.guess_attempts, 3);
+    assert!(games.guess_message.contains("found 42 in 3 attempts"));
+}
+
+#[test]
+fn invalid_guesses_do_not_count() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(1234);
+
+    games.update(GameMsg::GuessInputChanged("abc".to_string()), &mut platform);
+    games.update(GameMsg::SubmitGuess, &mut platform);
+    assert_eq!(games.guess_message, "Please enter a valid number!");
+
+    games.update(GameMsg::GuessInputChanged("101".to_string()), &mut platform);
+    games.update(GameMsg::SubmitGuess, &mut platform);
+    assert!(games.guess_message.contains("between 1 and 100"));
+    assert_eq!(games.guess_attempts, 0);
+    assert!(games.guess_input.is_empty());
+}
+
+#[test]
+fn new_guess_game_picks_a_target_in_range() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(1234);
+    for _ in 0..50 {
+        games.update(GameMsg::NewGuessGame, &mut platform);
+        assert!((1..=100).contains(&games.guess_target));
+        assert_eq!(games.guess_attempts, 0);
+        assert!(!games.guess_game_over);
+    }
+}
+
+#[test]
+fn the_same_seed_plays_out_the_same() {
+    let mut platform = TestPlatform::default();
+    let play = |platform: &mut TestPlatform| {
+        let mut games = GameState::new(2024);
+        let mut computer_moves = Vec::new();
+        for _ in 0..10 {
+            games.update(GameMsg::RockPaperScissorsPlay(Move::Paper), platform);
+            computer_moves.push(games.rps_last_round().unwrap().computer);
+        }
+        games.update(GameMsg::StartMemoryGame, platform);
+        (games.guess_target, computer_moves, games.memory_cards)
+    };
+    let (guess_target, computer_moves, memory_cards) = play(&mut platform);
+    assert_eq!(play(&mut platform), (guess_target, computer_moves, memory_cards.clone()));
+
+    let mut other = GameState::new(2025);
+    other.update(GameMsg::StartMemoryGame, &mut platform);
+    assert_ne!(other.memory_cards, memory_cards);
+}
+
+#[test]
+fn seeds_are_set_from_the_input_or_rolled() {
+    let mut platform = TestPlatform::with_rolls(&[0.5]);
+    let mut games = GameState::new(1);
+    games.update(GameMsg::GuessNumber(50), &mut platform);
+
+    games.update(GameMsg::SeedInputChanged(" 99 ".to_string()), &mut platform);
+    games.update(GameMsg::ApplySeed, &mut platform);
+    assert_eq!(games.seed, 99);
+    assert_eq!(games.guess_attempts, 0, "reseeding restarts the games");
+    assert_eq!(games.guess_target, GameState::new(99).guess_target);
+
+    games.update(GameMsg::SeedInputChanged("not a seed".to_string()), &mut platform);
+    games.update(GameMsg::ApplySeed, &mut platform);
+    assert_eq!(games.seed, 99);
+    assert_eq!(games.seed_input, "99");
+
+    games.update(GameMsg::NewSeed, &mut platform);
+    assert_eq!(games.seed, 500_000);
+    assert_eq!(games.seed_input, "500000");
+}
+
+#[test]
+fn reseeding_keeps_how_the_games_are_set_up() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(1);
+    games.update(GameMsg::SelectGuessDifficulty(Difficulty::Hard), &mut platform);
+    games.update(GameMsg::SelectRpsVariant(Variant::LizardSpock), &mut platform);
+    games.update(GameMsg::SelectRpsStrategy(Strategy::Frequency), &mut platform);
+    games.update(GameMsg::SelectRpsMatchLength(Some(5)), &mut platform);
+    games.update(GameMsg::RockPaperScissorsPlay(Move::Spock), &mut platform);
+    games.update(GameMsg::GuessNumber(500), &mut platform);
+
+    games.update(GameMsg::SeedInputChanged("42".to_string()), &mut platform);
+    games.update(GameMsg::ApplySeed, &mut platform);
+    assert_eq!(games.guess_difficulty, Difficulty::Hard);
+    assert_eq!(games.guess_rules, Difficulty::Hard.rules().unwrap());
+    assert_eq!(games.guess_attempts, 0);
+    assert_eq!(games.rps_variant, Variant::LizardSpock);
+    assert_eq!(games.rps_strategy, Strategy::Frequency);
+    assert_eq!(games.rps_match, Some(Match::new(5)));
+    assert!(games.rps_rounds.is_empty());
+
+    // The target only depends on the seed and the rules
+    let mut again = GameState::new(7);
+    again.update(GameMsg::SelectGuessDifficulty(Difficulty::Hard), &mut platform);
+    again.reseed(42);
+    assert_eq!(again.guess_target, games.guess_target);
+}
+
+#[test]
+fn seeds_come_from_the_url() {
+    assert_eq!(seed_from_query(&Query::parse("?seed=1234")), Some(1234));
+    assert_eq!(seed_from_query(&Query::parse("?seed=abc")), None);
+    assert_eq!(seed_from_query(&Query::default()), None);
+}
+
+/// Index of the other card showing the same emoji as card `index`
+fn partner(games: &GameState, index: usize) -> usize {
+    (0..games.memory_cards.len())
+        .find(|&other| other != index && games.memory_cards[other].0 == games.memory_cards[index].0)
+        .expect("every card has a partner")
+}
+
+#[test]
+fn memory_deals_eight_pairs() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(1234);
+    games.update(GameMsg::StartMemoryGame, &mut platform);
+
+    assert_eq!(games.memory_cards.len(), 16);
+    for index in 0..16 {
+        partner(&games, index);
+    }
+    assert!(games.memory_cards.iter().all(|(_, revealed, matched)| !revealed && !matched));
+}
+
+#[test]
+fn memory_matches_and_mismatches() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(1234);
+    games.update(GameMsg::StartMemoryGame, &mut platform);
+
+    let first = 0;
+    let mismatch = (1..16).find(|&index| index != partner(&games, first)).unwrap();
+    games.update(GameMsg::MemoryCardClick(first), &mut platform);
+    games.update(GameMsg::MemoryCardClick(mismatch), &mut platform);
+    assert_eq!(games.memory_moves, 1);
+    assert!(games.memory_showing_pair);
+    assert!(games.memory_in_progress());
+
+    // The next click turns the mismatched pair back over
+    let matching = partner(&games, first);
+    games.update(GameMsg::MemoryCardClick(matching), &mut platform);
+    assert!(!games.memory_cards[first].1 && !games.memory_cards[mismatch].1);
+    games.update(GameMsg::MemoryCardClick(first), &mut platform);
+    assert!(games.memory_cards[first].2 && games.memory_cards[matching].2);
+    assert_eq!(games.memory_matches, 1);
+    assert_eq!(games.memory_moves, 2);
+
+    // Clicking a matched card does nothing
+    games.update(GameMsg::MemoryCardClick(first), &mut platform);
+    assert_eq!(games.memory_first_card, None);
+}
+
+#[test]
+fn memory_is_won_once_every_pair_is_found() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(1234);
+    games.update(GameMsg::StartMemoryGame, &mut platform);
+
+    for index in 0..16 {
+        if games.memory_cards[index].2 {
+            continue;
+        }
+        let other = partner(&games, index);
+        games.update(GameMsg::MemoryCardClick(index), &mut platform);
+        games.update(GameMsg::MemoryCardClick(other), &mut platform);
+    }
+    assert!(games.memory_game_over);
+    assert_eq!(games.memory_moves, 8);
+    assert!(!games.memory_in_progress());
+    assert!(platform.logged("Game completed!"));
+}
+
+#[test]
+fn game_urls_can_pick_the_guessing_difficulty() {
+    let params = Query::parse("?seed=3&difficulty=hard");
+    assert_eq!(difficulty_from_query(&params), Some(Difficulty::Hard));
+    assert_eq!(difficulty_from_query(&Query::parse("?difficulty=%20easy")), Some(Difficulty::Easy));
+    assert_eq!(difficulty_from_query(&Query::parse("?difficulty=impossible")), None);
+    assert_eq!(difficulty_from_query(&Query::default()), None);
+}
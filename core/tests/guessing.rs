@@ -0,0 +1,197 @@
+mod common;
+
+use common::TestPlatform;
+use counter_core::games::{GameMsg, GameState};
+use counter_core::guessing::{quick_picks, Difficulty, GuessRules};
+
+#[test]
+fn presets_pick_targets_in_their_range() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(5);
+    for difficulty in [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard] {
+        games.update(GameMsg::SelectGuessDifficulty(difficulty), &mut platform);
+        let rules = difficulty.rules().unwrap();
+        assert_eq!(games.guess_rules, rules);
+        assert!(rules.contains(games.guess_target));
+        assert_eq!(games.guess_message, format!("Guess a number between {} and {}!", rules.min, rules.max));
+    }
+}
+
+#[test]
+fn custom_rules_are_checked() {
+    assert_eq!(GuessRules::parse("10", "20", ""), Ok(GuessRules { min: 10, max: 20, max_attempts: None }));
+    assert_eq!(GuessRules::parse(" -5 ", "5", "0"), Ok(GuessRules { min: -5, max: 5, max_attempts: None }));
+    assert_eq!(GuessRules::parse("1", "8", "3"), Ok(GuessRules { min: 1, max: 8, max_attempts: Some(3) }));
+    assert!(GuessRules::parse("x", "8", "").is_err());
+    assert!(GuessRules::parse("8", "8", "").is_err());
+    assert!(GuessRules::parse("0", "2000000", "").is_err());
+    assert!(GuessRules::parse("1", "8", "-1").is_err());
+}
+
+#[test]
+fn custom_games_start_once_applied() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(5);
+    games.update(GameMsg::SelectGuessDifficulty(Difficulty::Custom), &mut platform);
+    assert_eq!(games.guess_rules, GuessRules::default(), "picking custom alone changes nothing");
+
+    games.update(GameMsg::GuessCustomMinChanged("500".to_string()), &mut platform);
+    games.update(GameMsg::GuessCustomMaxChanged("510".to_string()), &mut platform);
+    games.update(GameMsg::GuessCustomAttemptsChanged("2".to_string()), &mut platform);
+    games.update(GameMsg::ApplyGuessCustom, &mut platform);
+    assert_eq!(games.guess_rules, GuessRules { min: 500, max: 510, max_attempts: Some(2) });
+    assert!((500..=510).contains(&games.guess_target));
+
+    games.update(GameMsg::GuessCustomMaxChanged("400".to_string()), &mut platform);
+    games.update(GameMsg::ApplyGuessCustom, &mut platform);
+    assert_eq!(games.guess_message, "The highest number has to be above the lowest");
+    assert_eq!(games.guess_rules.max, 510);
+
+    games.update(GameMsg::GuessInputChanged("100".to_string()), &mut platform);
+    games.update(GameMsg::SubmitGuess, &mut platform);
+    assert_eq!(games.guess_message, "Please enter a number between 500 and 510!");
+}
+
+#[test]
+fn running_out_of_guesses_loses() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(5);
+    games.guess_target = 77;
+    for guess in 1..=9 {
+        games.update(GameMsg::GuessNumber(guess), &mut platform);
+    }
+    assert_eq!(games.guesses_left(), Some(1));
+    assert!(!games.guess_game_over);
+
+    games.update(GameMsg::GuessNumber(10), &mut platform);
+    assert!(games.guess_game_over);
+    assert!(!games.guess_won);
+    assert_eq!(games.guess_message, "💥 Out of guesses! The number was 77.");
+
+    games.update(GameMsg::GuessNumber(77), &mut platform);
+    assert_eq!(games.guess_attempts, 10, "no guessing after the game is over");
+}
+
+#[test]
+fn the_last_guess_can_still_win() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(5);
+    games.update(GameMsg::SelectGuessDifficulty(Difficulty::Hard), &mut platform);
+    games.guess_target = 10;
+    for guess in 1..=10 {
+        games.update(GameMsg::GuessNumber(guess), &mut platform);
+    }
+    assert!(games.guess_won);
+    assert_eq!(games.guesses_left(), Some(0));
+}
+
+#[test]
+fn easy_games_have_no_limit() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(5);
+    games.update(GameMsg::SelectGuessDifficulty(Difficulty::Easy), &mut platform);
+    games.guess_target = 50;
+    for guess in 1..50 {
+        games.update(GameMsg::GuessNumber(guess), &mut platform);
+    }
+    assert!(!games.guess_game_over);
+    assert_eq!(games.guesses_left(), None);
+}
+
+#[test]
+fn feedback_narrows_the_known_bounds() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(5);
+    games.guess_target = 42;
+    games.update(GameMsg::GuessNumber(30), &mut platform);
+    games.update(GameMsg::GuessNumber(60), &mut platform);
+    assert_eq!((games.guess_low, games.guess_high), (31, 59));
+    assert_eq!(quick_picks(games.guess_low, games.guess_high), vec![31, 38, 45, 52, 59]);
+
+    games.update(GameMsg::NewGuessGame, &mut platform);
+    assert_eq!((games.guess_low, games.guess_high), (1, 100));
+}
+
+#[test]
+fn quick_picks_spread_over_the_bounds() {
+    assert_eq!(quick_picks(1, 100), vec![1, 25, 50, 75, 100]);
+    assert_eq!(quick_picks(7, 9), vec![7, 8, 9]);
+    assert_eq!(quick_picks(4, 4), vec![4]);
+}
+
+#[test]
+fn optimal_guesses_follow_binary_search() {
+    let rules = |min, max| GuessRules { min, max, max_attempts: None };
+    assert_eq!(rules(1, 100).optimal_guesses(), 7);
+    assert_eq!(rules(1, 1000).optimal_guesses(), 10);
+    assert_eq!(rules(1, 2).optimal_guesses(), 2);
+    assert_eq!(rules(1, 3).optimal_guesses(), 2);
+    assert_eq!(rules(1, 1024).optimal_guesses(), 11);
+}
+
+/// Guess the middle of the known bounds until the game ends, as binary search would
+fn bisect(games: &mut GameState, platform: &mut TestPlatform) {
+    while !games.guess_game_over {
+        let guess = games.guess_low + (games.guess_high - games.guess_low) / 2;
+        games.update(GameMsg::GuessNumber(guess), platform);
+    }
+}
+
+#[test]
+fn bisecting_never_needs_more_than_the_optimum() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(5);
+    games.update(GameMsg::SelectGuessDifficulty(Difficulty::Easy), &mut platform);
+    let optimal = games.guess_rules.optimal_guesses();
+    for target in 1..=50 {
+        games.update(GameMsg::NewGuessGame, &mut platform);
+        games.guess_target = target;
+        bisect(&mut games, &mut platform);
+        assert!(games.guess_won);
+        assert!(games.guess_attempts <= optimal, "{} took {}", target, games.guess_attempts);
+        assert_eq!(games.guess_wasted, 0);
+    }
+}
+
+#[test]
+fn guesses_contradicting_feedback_are_flagged() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(5);
+    games.guess_target = 42;
+
+    games.update(GameMsg::GuessNumber(50), &mut platform);
+    assert_eq!(games.guess_warning, None);
+    games.update(GameMsg::GuessNumber(70), &mut platform);
+    assert_eq!(games.guess_warning.as_deref(), Some("⚠️ Wasted guess: you already knew it was at most 49"));
+    assert_eq!(games.guess_high, 49, "a wasted guess doesn't widen the bounds");
+
+    games.update(GameMsg::GuessNumber(20), &mut platform);
+    assert_eq!(games.guess_warning, None);
+    games.update(GameMsg::GuessNumber(20), &mut platform);
+    assert_eq!(games.guess_warning.as_deref(), Some("⚠️ Wasted guess: you already knew it was at least 21"));
+    assert_eq!(games.guess_wasted, 2);
+    assert_eq!(games.guess_analysis(), None, "no analysis until the game is over");
+
+    games.update(GameMsg::GuessNumber(42), &mut platform);
+    assert_eq!(games.guess_warning, None);
+    assert_eq!(
+        games.guess_analysis().as_deref(),
+        Some("Binary search always finds a number from 1 to 100 within 7 guesses; you took 5. 2 of them were already ruled out.")
+    );
+
+    games.update(GameMsg::NewGuessGame, &mut platform);
+    assert_eq!(games.guess_wasted, 0);
+}
+
+#[test]
+fn lost_games_are_analysed_too() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(5);
+    games.guess_target = 100;
+    for _ in 0..10 {
+        games.update(GameMsg::GuessNumber(1), &mut platform);
+    }
+    assert!(games.guess_game_over && !games.guess_won);
+    assert_eq!(games.guess_wasted, 9);
+    assert!(games.guess_analysis().unwrap().contains("you took 10. 9 of them were already ruled out."));
+}
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use counter_core::platform::Random;
+use counter_core::rng::{shuffle, SeededRandom};
+
+#[test]
+fn seeds_repeat_their_sequence() {
+    let mut a = SeededRandom::new(1234);
+    let mut b = SeededRandom::new(1234);
+    let mut c = SeededRandom::new(1235);
+    let first: Vec<u64> = (0..5).map(|_| a.next_u64()).collect();
+    assert_eq!(first, (0..5).map(|_| b.next_u64()).collect::<Vec<_>>());
+    assert_ne!(first, (0..5).map(|_| c.next_u64()).collect::<Vec<_>>());
+}
+
+#[test]
+fn ranges_are_inclusive_and_in_bounds() {
+    let mut random = SeededRandom::new(0);
+    let mut seen = [false; 6];
+    for _ in 0..1000 {
+        let value = random.range(1, 6);
+        assert!((1..=6).contains(&value));
+        seen[value as usize - 1] = true;
+    }
+    assert!(seen.iter().all(|&seen| seen));
+}
+
+#[test]
+fn shuffle_keeps_every_item() {
+    let mut random = SeededRandom::new(42);
+    let mut items: Vec<u32> = (0..16).collect();
+    shuffle(&mut items, &mut random);
+    let mut sorted = items.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..16).collect::<Vec<_>>());
+    assert_ne!(items, sorted);
+}
+
+#[test]
+fn shuffle_is_unbiased() {
+    // Swapping with any position turns up some of the six orders of three items a quarter
+    // more often than others; Fisher–Yates gives each a sixth
+    let mut random = SeededRandom::new(7);
+    let mut counts: HashMap<[u8; 3], u32> = HashMap::new();
+    let rounds = 60_000;
+    for _ in 0..rounds {
+        let mut items = [0, 1, 2];
+        shuffle(&mut items, &mut random);
+        *counts.entry(items).or_default() += 1;
+    }
+    assert_eq!(counts.len(), 6);
+    for (order, count) in counts {
+        let share = count as f64 / rounds as f64;
+        assert!((share - 1.0 / 6.0).abs() < 0.01, "{:?} came up {:.3} of the time", order, share);
+    }
+}
@@ -0,0 +1,74 @@
+use counter_core::games::GameKind;
+use counter_core::routes::{percent_encode, Query, Route};
+
+#[test]
+fn parses_every_page() {
+    assert_eq!(Route::from_url("/"), Route::Home);
+    assert_eq!(Route::from_url("/counter"), Route::Counter);
+    assert_eq!(Route::from_url("/cats/"), Route::Cats);
+    assert_eq!(Route::from_url("/cats/3"), Route::Cat("3".to_string()));
+    assert_eq!(Route::from_url("/games"), Route::games());
+    assert_eq!(Route::from_url("/favorites"), Route::Favorites);
+    assert_eq!(Route::from_url("/settings"), Route::Settings);
+    assert_eq!(Route::from_url("/about"), Route::About);
+}
+
+#[test]
+fn unknown_and_malformed_urls_are_not_found() {
+    for url in ["/nope", "/cats/a/b", "/games/chess", "/counter/1"] {
+        assert_eq!(Route::from_url(url), Route::NotFound(url.to_string()), "{}", url);
+    }
+}
+
+#[test]
+fn game_routes_keep_their_query() {
+    let route = Route::from_url("/games/memory?size=4&name=a%20b");
+    let Route::Game { kind, params } = &route else { panic!("not a game route: {:?}", route) };
+    assert_eq!(*kind, GameKind::Memory);
+    assert_eq!(params.get("size").as_deref(), Some("4"));
+    assert_eq!(params.get("name").as_deref(), Some("a b"));
+    assert_eq!(route.to_url(), "/games/memory?size=4&name=a%20b");
+}
+
+#[test]
+fn the_games_overview_keeps_its_query() {
+    let route = Route::from_url("/games?seed=1234");
+    assert_eq!(route.game_params().and_then(|params| params.get("seed")).as_deref(), Some("1234"));
+    assert_eq!(route.to_url(), "/games?seed=1234");
+    assert_eq!(Route::from_hash("#/games?seed=1234"), route);
+}
+
+#[test]
+fn urls_round_trip() {
+    let routes = [
+        Route::Home,
+        Route::Counter,
+        Route::Cat("J8eYm0Ts6ZzqAvAf".to_string()),
+        Route::Cat("cats/tabby cat.jpg".to_string()),
+        Route::Game { kind: GameKind::NumberGuessing, params: Query::default() },
+        Route::About,
+    ];
+    for route in routes {
+        assert_eq!(Route::from_url(&route.to_url()), route);
+    }
+}
+
+#[test]
+fn hash_urls_parse_like_paths() {
+    assert_eq!(Route::from_hash("#/cats/7"), Route::Cat("7".to_string()));
+    assert_eq!(Route::from_hash(""), Route::Home);
+}
+
+#[test]
+fn sections_group_detail_pages() {
+    assert_eq!(Route::Cat("5".to_string()).section(), Route::Cats);
+    assert_eq!(Route::from_url("/games/rock-paper-scissors").section(), Route::games());
+    assert_eq!(Route::from_url("/games?seed=1").section(), Route::games());
+    assert_eq!(Route::Counter.section(), Route::Counter);
+}
+
+#[test]
+fn percent_encoding_escapes_reserved_characters() {
+    assert_eq!(percent_encode("cute cat/&"), "cute%20cat%2F%26");
+    assert_eq!(Query::parse("?tag=cute%20cat%2F%26").get("tag").as_deref(), Some("cute cat/&"));
+}
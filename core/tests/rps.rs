@@ -0,0 +1,80 @@
+use counter_core::rps::{Move, Outcome, Variant};
+
+#[test]
+fn classic_rules() {
+    let classic = Variant::Classic;
+    assert_eq!(classic.outcome(Move::Rock, Move::Scissors), Outcome::Win);
+    assert_eq!(classic.outcome(Move::Paper, Move::Rock), Outcome::Win);
+    assert_eq!(classic.outcome(Move::Scissors, Move::Paper), Outcome::Win);
+    assert_eq!(classic.outcome(Move::Rock, Move::Paper), Outcome::Loss);
+    assert_eq!(classic.outcome(Move::Paper, Move::Scissors), Outcome::Loss);
+    assert_eq!(classic.outcome(Move::Scissors, Move::Rock), Outcome::Loss);
+    assert_eq!(classic.outcome(Move::Paper, Move::Paper), Outcome::Tie);
+    assert_eq!(classic.describe(Move::Paper, Move::Scissors), "✂️ Scissors cuts 📄 Paper");
+}
+
+#[test]
+fn lizard_spock_rules() {
+    let variant = Variant::LizardSpock;
+    assert_eq!(variant.beats(Move::Spock, Move::Rock), Some("vaporizes"));
+    assert_eq!(variant.beats(Move::Lizard, Move::Spock), Some("poisons"));
+    assert_eq!(variant.outcome(Move::Rock, Move::Lizard), Outcome::Win);
+    assert_eq!(variant.outcome(Move::Scissors, Move::Spock), Outcome::Loss);
+    assert_eq!(variant.describe(Move::Lizard, Move::Lizard), "Both played 🦎 Lizard");
+}
+
+#[test]
+fn classic_ignores_moves_it_does_not_have() {
+    assert_eq!(Variant::Classic.beats(Move::Rock, Move::Lizard), None);
+    assert_eq!(Variant::Classic.outcome(Move::Spock, Move::Rock), Outcome::Tie);
+}
+
+/// Every move beats as many moves as it loses to, and every pair has exactly one winner
+fn assert_balanced(variant: Variant) {
+    let moves = variant.moves();
+    for &a in &moves {
+        let wins = moves.iter().filter(|&&b| variant.outcome(a, b) == Outcome::Win).count();
+        let losses = moves.iter().filter(|&&b| variant.outcome(a, b) == Outcome::Loss).count();
+        assert_eq!(wins, (moves.len() - 1) / 2, "{:?} in {:?}", a, variant);
+        assert_eq!(losses, wins, "{:?} in {:?}", a, variant);
+        for &b in &moves {
+            let tie = variant.outcome(a, b) == Outcome::Tie;
+            assert_eq!(tie, a == b, "{:?} vs {:?} in {:?}", a, b, variant);
+        }
+    }
+}
+
+#[test]
+fn every_variant_is_balanced() {
+    for variant in Variant::choices() {
+        assert_balanced(variant);
+    }
+    for count in Variant::MIN_CYCLIC_MOVES..=Variant::MAX_CYCLIC_MOVES {
+        assert_balanced(Variant::cyclic(count));
+    }
+}
+
+#[test]
+fn cyclic_moves_beat_the_ones_just_before_them() {
+    let variant = Variant::cyclic(7);
+    assert_eq!(variant.moves().len(), 7);
+    assert_eq!(variant.outcome(Move::Numbered(3), Move::Numbered(0)), Outcome::Win);
+    assert_eq!(variant.outcome(Move::Numbered(4), Move::Numbered(0)), Outcome::Loss);
+    assert_eq!(variant.outcome(Move::Numbered(0), Move::Numbered(6)), Outcome::Win);
+    assert_eq!(variant.outcome(Move::Rock, Move::Numbered(0)), Outcome::Tie);
+}
+
+#[test]
+fn cyclic_variants_have_an_odd_number_of_moves_in_range() {
+    assert_eq!(Variant::cyclic(0), Variant::Cyclic(3));
+    assert_eq!(Variant::cyclic(8), Variant::Cyclic(9));
+    assert_eq!(Variant::cyclic(200), Variant::Cyclic(15));
+}
+
+#[test]
+fn slugs_round_trip() {
+    for variant in Variant::choices() {
+        assert_eq!(Variant::from_slug(&variant.slug()), variant);
+    }
+    assert_eq!(Variant::from_slug("nonsense"), Variant::Classic);
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use common::TestPlatform;
+use counter_core::games::{GameMsg, GameState};
+use counter_core::rng::SeededRandom;
+use counter_core::rps::{Move, Variant};
+use counter_core::strategies::{play_off, Strategy};
+
+use Move::{Paper, Rock, Scissors};
+
+#[test]
+fn strategies_predict_from_the_opponents_moves() {
+    let moves = [Rock, Rock, Paper, Scissors];
+    assert_eq!(Strategy::Random.predict(&moves), None);
+    assert_eq!(Strategy::BeatLast.predict(&moves), Some(Scissors));
+    assert_eq!(Strategy::Frequency.predict(&moves), Some(Rock));
+    assert_eq!(Strategy::Frequency.predict(&[]), None);
+}
+
+#[test]
+fn frequency_ties_go_to_the_latest_move() {
+    assert_eq!(Strategy::Frequency.predict(&[Rock, Paper, Paper, Rock]), Some(Rock));
+}
+
+#[test]
+fn markov_follows_patterns() {
+    let cycle = [Rock, Paper, Scissors, Rock, Paper, Scissors, Rock, Paper];
+    assert_eq!(Strategy::Markov(1).predict(&cycle), Some(Scissors));
+    assert_eq!(Strategy::Markov(2).predict(&cycle), Some(Scissors));
+
+    // After Rock it has always been Paper, even though Rock is more common overall
+    let moves = [Rock, Paper, Rock, Paper, Scissors, Scissors, Scissors, Scissors, Rock];
+    assert_eq!(Strategy::Markov(1).predict(&moves), Some(Paper));
+    assert_eq!(Strategy::Frequency.predict(&moves), Some(Scissors));
+
+    // An unseen context backs off to frequency
+    assert_eq!(Strategy::Markov(2).predict(&[Paper]), Some(Paper));
+}
+
+#[test]
+fn choices_beat_the_prediction() {
+    let mut random = SeededRandom::new(5);
+    for _ in 0..20 {
+        assert_eq!(Strategy::BeatLast.choose(Variant::Classic, &[Rock], &mut random), Paper);
+        let lizard_spock = Strategy::BeatLast.choose(Variant::LizardSpock, &[Rock], &mut random);
+        assert!([Paper, Move::Spock].contains(&lizard_spock));
+        let cyclic = Strategy::BeatLast.choose(Variant::cyclic(7), &[Move::Numbered(2)], &mut random);
+        assert!([Move::Numbered(3), Move::Numbered(4), Move::Numbered(5)].contains(&cyclic));
+    }
+}
+
+#[test]
+fn play_offs_are_headless_and_reproducible() {
+    let play = |seed| play_off(Variant::Classic, Strategy::Markov(2), Strategy::BeatLast, 300, &mut SeededRandom::new(seed));
+    let record = play(9);
+    assert_eq!(record.rounds(), 300);
+    assert_eq!(record, play(9));
+    assert!(record.wins > 10 * record.losses, "{:?}", record);
+}
+
+#[test]
+fn random_can_not_be_exploited() {
+    let mut random = SeededRandom::new(11);
+    for opponent in Strategy::ALL {
+        let rate = play_off(Variant::Classic, opponent, Strategy::Random, 3000, &mut random).win_rate().unwrap();
+        assert!((rate - 1.0 / 3.0).abs() < 0.05, "{:?} won {:.3} against random", opponent, rate);
+    }
+}
+
+#[test]
+fn adaptive_opponents_punish_a_predictable_player() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(3);
+    games.update(GameMsg::SelectRpsStrategy(Strategy::Frequency), &mut platform);
+    for _ in 0..50 {
+        games.update(GameMsg::RockPaperScissorsPlay(Rock), &mut platform);
+    }
+    let record = games.rps_strategy_record();
+    assert_eq!(record.rounds(), 50);
+    assert!(record.wins >= 49, "{:?}", record);
+    assert_eq!(games.rps_score().losses, record.wins);
+
+    // Each strategy keeps its own record
+    games.update(GameMsg::SelectRpsStrategy(Strategy::Random), &mut platform);
+    assert_eq!(games.rps_strategy_record().rounds(), 0);
+}
+
+#[test]
+fn switching_variants_forgets_the_players_moves() {
+    let mut platform = TestPlatform::default();
+    let mut games = GameState::new(3);
+    games.update(GameMsg::RockPaperScissorsPlay(Rock), &mut platform);
+    assert_eq!(games.rps_history, vec![Rock]);
+    games.update(GameMsg::SelectRpsVariant(Variant::LizardSpock), &mut platform);
+    assert!(games.rps_history.is_empty());
+}
+
+#[test]
+fn slugs_round_trip() {
+    for strategy in Strategy::ALL {
+        assert_eq!(Strategy::from_slug(&strategy.slug()), strategy);
+    }
+}
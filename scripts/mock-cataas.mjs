@@ -0,0 +1,117 @@
+// A stand-in for cataas.com that runs offline, for trying out and testing the cat search.
+//
+//   node scripts/mock-cataas.mjs            # listens on http://localhost:8081, PORT overrides it
+//
+// Then open the app with `?cataas-url=http://localhost:8081` to point the cataas source at it.
+//
+// Speaks the parts of the cataas API the app uses:
+//   GET /cat                          random cat
+//   GET /cat/:tag                     random cat with that tag
+//   GET /cat/:id                      that specific cat
+//   GET /cat[/:tag|/:id]/says/:text   same, with a caption
+//   GET /api/tags                     every known tag
+//   GET /api/cats?tags=a,b&limit=&skip=
+// `?json=true` returns the cat's metadata instead of the image, `type=square|medium|small|xsmall`
+// picks a size and `filter=mono|negate|blur|sepia|paint|pixel` applies a filter.
+// Images are SVGs showing the id, tags and caption, so a test can tell which cat it got.
+
+import { createServer } from "node:http";
+
+const PORT = Number(process.env.PORT ?? 8081);
+
+const CATS = [
+    { id: "mock00000000cat1", tags: ["cute", "orange"] },
+    { id: "mock00000000cat2", tags: ["cute", "kitten"] },
+    { id: "mock00000000cat3", tags: ["orange", "sleepy"] },
+    { id: "mock00000000cat4", tags: ["black"] },
+    { id: "mock00000000cat5", tags: ["black", "sleepy"] },
+    { id: "mock00000000cat6", tags: ["kitten", "white"] },
+    { id: "mock00000000cat7", tags: ["white", "fluffy"] },
+    { id: "mock00000000cat8", tags: ["fluffy", "cute"] },
+    { id: "mock00000000cat9", tags: [] },
+];
+
+const SIZES = { square: [400, 400], medium: [600, 450], small: [320, 240], xsmall: [160, 120] };
+
+const FILTERS = {
+    mono: `<feColorMatrix type="saturate" values="0"/>`,
+    negate: `<feComponentTransfer><feFuncR type="table" tableValues="1 0"/><feFuncG type="table" tableValues="1 0"/><feFuncB type="table" tableValues="1 0"/></feComponentTransfer>`,
+    blur: `<feGaussianBlur stdDeviation="4"/>`,
+    sepia: `<feColorMatrix type="matrix" values=".39 .77 .19 0 0 .35 .69 .17 0 0 .27 .53 .13 0 0 0 0 0 1 0"/>`,
+    paint: `<feMorphology operator="dilate" radius="2"/>`,
+    pixel: `<feComponentTransfer><feFuncR type="discrete" tableValues="0 .5 1"/><feFuncG type="discrete" tableValues="0 .5 1"/><feFuncB type="discrete" tableValues="0 .5 1"/></feComponentTransfer>`,
+};
+
+const escapeXml = (text) =>
+    text.replace(/[<>&'"]/g, (c) => ({ "<": "&lt;", ">": "&gt;", "&": "&amp;", "'": "&apos;", '"': "&quot;" })[c]);
+
+function metadata(cat) {
+    return { id: cat.id, tags: cat.tags, mimetype: "image/svg+xml", createdAt: "2024-01-01T00:00:00.000Z" };
+}
+
+function image(cat, says, params) {
+    const [width, height] = SIZES[params.get("type")] ?? [500, 400];
+    const filter = FILTERS[params.get("filter")];
+    const hue = [...cat.id].reduce((sum, c) => sum + c.charCodeAt(0), 0) % 360;
+    const caption = says
+        ? `<text x="50%" y="88%" font-size="28" text-anchor="middle" fill="white" stroke="black">${escapeXml(says)}</text>`
+        : "";
+    return `<svg xmlns="http://www.w3.org/2000/svg" width="${width}" height="${height}">
+${filter ? `<filter id="f">${filter}</filter>` : ""}
+<g ${filter ? `filter="url(#f)"` : ""}>
+<rect width="100%" height="100%" fill="hsl(${hue}, 70%, 75%)"/>
+<text x="50%" y="40%" font-size="24" text-anchor="middle" font-family="monospace">${cat.id}</text>
+<text x="50%" y="55%" font-size="18" text-anchor="middle" font-family="sans-serif">${escapeXml(cat.tags.join(", "))}</text>
+${caption}
+</g>
+</svg>`;
+}
+
+function send(response, status, contentType, body) {
+    response.writeHead(status, {
+        "Content-Type": contentType,
+        "Access-Control-Allow-Origin": "*",
+        // Lets the app's offline cache copy images out of the HTTP cache, like with the real cataas
+        "Cache-Control": "public, max-age=3600",
+    });
+    response.end(body);
+}
+
+const sendJson = (response, status, body) => send(response, status, "application/json", JSON.stringify(body));
+
+function serveCat(response, params, selector, says) {
+    const byId = CATS.find((cat) => cat.id === selector);
+    const candidates = byId ? [byId] : CATS.filter((cat) => !selector || cat.tags.includes(selector));
+    if (candidates.length === 0) {
+        return sendJson(response, 404, { message: "Cat not found" });
+    }
+    const cat = candidates[Math.floor(Math.random() * candidates.length)];
+    if (params.get("json") === "true") {
+        return sendJson(response, 200, metadata(cat));
+    }
+    send(response, 200, "image/svg+xml", image(cat, says, params));
+}
+
+createServer((request, response) => {
+    const url = new URL(request.url, `http://localhost:${PORT}`);
+    const segments = url.pathname.split("/").filter(Boolean).map(decodeURIComponent);
+    console.log(`${request.method} ${request.url}`);
+
+    if (segments[0] === "cat") {
+        const saysAt = segments.indexOf("says");
+        const says = saysAt === -1 ? "" : segments.slice(saysAt + 1).join("/");
+        const selector = saysAt === 1 || segments.length === 1 ? "" : segments[1];
+        return serveCat(response, url.searchParams, selector, says);
+    }
+    if (url.pathname === "/api/tags") {
+        return sendJson(response, 200, [...new Set(CATS.flatMap((cat) => cat.tags))].sort());
+    }
+    if (url.pathname === "/api/cats") {
+        const tags = (url.searchParams.get("tags") ?? "").split(",").filter(Boolean);
+        const skip = Number(url.searchParams.get("skip") ?? 0);
+        const limit = Number(url.searchParams.get("limit") ?? 10);
+        const cats = CATS.filter((cat) => tags.every((tag) => cat.tags.includes(tag)));
+        return sendJson(response, 200, cats.slice(skip, skip + limit).map(metadata));
+    }
+    sendJson(response, 404, { message: "Not found" });
+}).listen(PORT, () => console.log(`Mock cataas listening on http://localhost:${PORT}`));
@@ -0,0 +1,91 @@
+use sauron::{Program, wasm_bindgen::closure::Closure, wasm_bindgen::JsCast};
+use std::cell::RefCell;
+use std::rc::Rc;
+use web_sys::{console, window};
+
+use crate::{App, Msg};
+
+/// Turns browser callbacks into `Msg`s for the running program.
+///
+/// Holds the `Program` handle returned from mounting, so anything that lives
+/// outside of `App::update` (window events, timers, image callbacks) can feed
+/// the app the same way a click in the view does. The app itself gets a clone
+/// before it is mounted; messages sent until `attach` is called are queued.
+#[derive(Clone)]
+pub struct Bridge {
+    program: Rc<RefCell<Option<Program<App>>>>,
+    pending: Rc<RefCell<Vec<Msg>>>,
+}
+
+impl Bridge {
+    pub fn new() -> Self {
+        Bridge {
+            program: Rc::new(RefCell::new(None)),
+            pending: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Connect to the mounted program and deliver anything queued so far
+    pub fn attach(&self, program: &Program<App>) {
+        *self.program.borrow_mut() = Some(program.clone());
+
+        let pending: Vec<Msg> = self.pending.borrow_mut().drain(..).collect();
+        if !pending.is_empty() {
+            program.clone().dispatch_multiple(pending);
+        }
+    }
+
+    /// Queue a message for the program; it is processed after the current update returns.
+    pub fn dispatch(&self, msg: Msg) {
+        let program = self.program.borrow().clone();
+        match program {
+            Some(mut program) => program.dispatch(msg),
+            None => self.pending.borrow_mut().push(msg),
+        }
+    }
+
+    /// Dispatch `msg` once `delay_ms` milliseconds have passed
+    pub fn dispatch_after(&self, delay_ms: i32, msg: Msg) {
+        let Some(window) = window() else {
+            return;
+        };
+
+        let bridge = self.clone();
+        // A one-shot closure is freed by wasm-bindgen after it runs, so nothing leaks
+        let callback = Closure::once_into_js(move || bridge.dispatch(msg));
+        if window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(callback.unchecked_ref(), delay_ms)
+            .is_err()
+        {
+            console::log_1(&"Failed to schedule a timer".into());
+        }
+    }
+
+    /// Listen to `event_name` on the window for the lifetime of the app,
+    /// dispatching whatever message `to_msg` derives from the event.
+    pub fn listen_window<F>(&self, event_name: &str, to_msg: F)
+    where
+        F: Fn(web_sys::Event) -> Option<Msg> + 'static,
+    {
+        let Some(window) = window() else {
+            return;
+        };
+
+        let bridge = self.clone();
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            if let Some(msg) = to_msg(event) {
+                bridge.dispatch(msg);
+            }
+        }) as Box<dyn Fn(web_sys::Event)>);
+
+        if window
+            .add_event_listener_with_callback(event_name, closure.as_ref().unchecked_ref())
+            .is_err()
+        {
+            console::log_1(&format!("Failed to listen to window event: {}", event_name).into());
+        }
+
+        // Window listeners live as long as the page does
+        closure.forget();
+    }
+}
@@ -0,0 +1,352 @@
+use sauron::wasm_bindgen::{JsCast, JsValue};
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{console, window, Blob, Cache, RequestCache, RequestInit, RequestMode, Response, Url};
+
+use crate::cats::{CatMetadata, CatQuery, CatSource, SourceRequest};
+
+/// Cache Storage bucket holding the image bytes
+const CACHE_NAME: &str = "sauron-demo-cats";
+
+/// localStorage key of the LRU bookkeeping, which Cache Storage can't keep itself
+const INDEX_KEY: &str = "sauron-demo.cat-cache";
+
+const DEFAULT_MAX_MB: f64 = 25.0;
+
+/// Failed images in a row after which the connection is treated as down
+const OFFLINE_AFTER_FAILURES: u32 = 3;
+
+#[derive(Debug, Clone)]
+struct Entry {
+    url: String,
+    bytes: f64,
+    last_used: f64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    /// Everything stored, least recently used first
+    entries: Vec<Entry>,
+    max_bytes: f64,
+    /// Object URLs for cached cats, by source URL, so they can be handed out without waiting
+    ready: Vec<(String, String)>,
+    /// Next ready cat to hand out
+    cursor: usize,
+}
+
+/// Image bytes of loaded cats, kept in Cache Storage so the browser still has cats offline.
+///
+/// The total size is capped; once it's exceeded the least recently shown cats are dropped.
+/// Configured with `<meta name="cat-cache" content="max-mb=25">`, `content="off"` disables it.
+#[derive(Clone)]
+pub struct CatCache {
+    state: Rc<RefCell<CacheState>>,
+}
+
+impl CatCache {
+    /// Read the configuration and saved index, then start loading cached cats in the background.
+    /// Returns `None` when caching is switched off or the browser has no Cache Storage.
+    pub fn from_document() -> Option<Self> {
+        let window = window()?;
+        let content = window
+            .document()
+            .and_then(|document| document.query_selector("meta[name='cat-cache']").ok().flatten())
+            .and_then(|meta| meta.get_attribute("content"))
+            .unwrap_or_default();
+        if content.trim() == "off" {
+            return None;
+        }
+        window.caches().ok()?;
+
+        let max_mb = content
+            .split(',')
+            .find_map(|setting| setting.trim().strip_prefix("max-mb="))
+            .and_then(|value| value.trim().parse::<f64>().ok())
+            .unwrap_or(DEFAULT_MAX_MB);
+
+        let cache = CatCache {
+            state: Rc::new(RefCell::new(CacheState {
+                entries: load_index(),
+                max_bytes: max_mb * 1024.0 * 1024.0,
+                ..CacheState::default()
+            })),
+        };
+        let warm_up = cache.clone();
+        spawn_local(async move {
+            if let Err(error) = warm_up.open_entries().await {
+                console::log_1(&format!("Couldn't read the cat cache: {:?}", error).into());
+            }
+        });
+        Some(cache)
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.borrow().entries.len()
+    }
+
+    pub fn total_bytes(&self) -> f64 {
+        self.state.borrow().entries.iter().map(|entry| entry.bytes).sum()
+    }
+
+    pub fn max_bytes(&self) -> f64 {
+        self.state.borrow().max_bytes
+    }
+
+    /// Up to `count` cached cats, cycling through them when there are fewer
+    pub fn take(&self, count: usize) -> Vec<String> {
+        let mut state = self.state.borrow_mut();
+        if state.ready.is_empty() {
+            return Vec::new();
+        }
+        let now = js_sys::Date::now();
+        let mut urls = Vec::with_capacity(count);
+        for _ in 0..count {
+            let index = state.cursor % state.ready.len();
+            state.cursor = index + 1;
+            let (source_url, object_url) = state.ready[index].clone();
+            if let Some(position) = state.entries.iter().position(|entry| entry.url == source_url) {
+                let mut entry = state.entries.remove(position);
+                entry.last_used = now;
+                state.entries.push(entry);
+            }
+            urls.push(object_url);
+        }
+        save_index(&state.entries);
+        urls
+    }
+
+    /// Copy a freshly loaded image into the cache, evicting old cats if it overflows
+    pub fn store(&self, url: &str) {
+        if self.state.borrow().entries.iter().any(|entry| entry.url == url) {
+            return;
+        }
+        let cache = self.clone();
+        let url = url.to_string();
+        spawn_local(async move {
+            if let Err(error) = cache.fetch_and_store(&url).await {
+                console::log_1(&format!("Couldn't cache {}: {:?}", url, error).into());
+            }
+        });
+    }
+
+    /// Forget every cached cat
+    pub fn clear(&self) {
+        {
+            let mut state = self.state.borrow_mut();
+            for (_, object_url) in state.ready.drain(..) {
+                let _ = Url::revoke_object_url(&object_url);
+            }
+            state.entries.clear();
+            state.cursor = 0;
+            save_index(&state.entries);
+        }
+        spawn_local(async {
+            let Some(caches) = window().and_then(|window| window.caches().ok()) else {
+                return;
+            };
+            if JsFuture::from(caches.delete(CACHE_NAME)).await.is_err() {
+                console::log_1(&"Couldn't delete the cat cache".into());
+            }
+        });
+    }
+
+    async fn open_entries(&self) -> Result<(), JsValue> {
+        let cache = open_cache().await?;
+        let urls: Vec<String> = self.state.borrow().entries.iter().rev().map(|entry| entry.url.clone()).collect();
+        let mut missing = Vec::new();
+        for url in urls {
+            let matched = JsFuture::from(cache.match_with_str(&url)).await?;
+            match matched.dyn_into::<Response>() {
+                Ok(response) => {
+                    let blob: Blob = JsFuture::from(response.blob()?).await?.dyn_into()?;
+                    let object_url = Url::create_object_url_with_blob(&blob)?;
+                    self.state.borrow_mut().ready.push((url, object_url));
+                }
+                // Evicted by the browser behind our back
+                Err(_) => missing.push(url),
+            }
+        }
+
+        let mut state = self.state.borrow_mut();
+        state.entries.retain(|entry| !missing.contains(&entry.url));
+        save_index(&state.entries);
+        console::log_1(&format!("Cat cache has {} cats ready for offline use", state.ready.len()).into());
+        Ok(())
+    }
+
+    async fn fetch_and_store(&self, url: &str) -> Result<(), JsValue> {
+        let window = window().ok_or("no window")?;
+        // The <img> just downloaded this URL, so take it from the HTTP cache rather than asking
+        // the server again, which would answer with a different random cat
+        let init = RequestInit::new();
+        init.set_cache(RequestCache::ForceCache);
+        init.set_mode(RequestMode::Cors);
+        let response: Response = JsFuture::from(window.fetch_with_str_and_init(url, &init)).await?.dyn_into()?;
+        if !response.ok() {
+            return Err(format!("HTTP {}", response.status()).into());
+        }
+
+        let cache = open_cache().await?;
+        let blob: Blob = JsFuture::from(response.clone()?.blob()?).await?.dyn_into()?;
+        JsFuture::from(cache.put_with_str(url, &response)).await?;
+        let object_url = Url::create_object_url_with_blob(&blob)?;
+
+        let evicted = {
+            let mut state = self.state.borrow_mut();
+            state.entries.push(Entry { url: url.to_string(), bytes: blob.size(), last_used: js_sys::Date::now() });
+            state.ready.push((url.to_string(), object_url));
+
+            let mut evicted = Vec::new();
+            let mut total: f64 = state.entries.iter().map(|entry| entry.bytes).sum();
+            while total > state.max_bytes && state.entries.len() > 1 {
+                let entry = state.entries.remove(0);
+                total -= entry.bytes;
+                evicted.push(entry.url);
+            }
+            state.ready.retain(|(source_url, object_url)| {
+                let keep = !evicted.contains(source_url);
+                if !keep {
+                    let _ = Url::revoke_object_url(object_url);
+                }
+                keep
+            });
+            save_index(&state.entries);
+            evicted
+        };
+
+        for url in evicted {
+            JsFuture::from(cache.delete_with_str(&url)).await?;
+        }
+        Ok(())
+    }
+}
+
+async fn open_cache() -> Result<Cache, JsValue> {
+    let caches = window().ok_or("no window")?.caches()?;
+    JsFuture::from(caches.open(CACHE_NAME)).await?.dyn_into()
+}
+
+fn load_index() -> Vec<Entry> {
+    let saved = window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .and_then(|storage| storage.get_item(INDEX_KEY).ok().flatten());
+    let Some(entries) = saved
+        .and_then(|saved| js_sys::JSON::parse(&saved).ok())
+        .and_then(|value| value.dyn_into::<js_sys::Array>().ok())
+    else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<Entry> = entries
+        .iter()
+        .filter_map(|entry| {
+            let field = |name: &str| js_sys::Reflect::get(&entry, &name.into()).ok();
+            Some(Entry {
+                url: field("url")?.as_string()?,
+                bytes: field("bytes")?.as_f64()?,
+                last_used: field("lastUsed")?.as_f64()?,
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.last_used.total_cmp(&b.last_used));
+    entries
+}
+
+fn save_index(entries: &[Entry]) {
+    let array = js_sys::Array::new();
+    for entry in entries {
+        let object = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&object, &"url".into(), &entry.url.as_str().into());
+        let _ = js_sys::Reflect::set(&object, &"bytes".into(), &entry.bytes.into());
+        let _ = js_sys::Reflect::set(&object, &"lastUsed".into(), &entry.last_used.into());
+        array.push(&object);
+    }
+    let Some(json) = js_sys::JSON::stringify(&array).ok().and_then(|json| json.as_string()) else {
+        return;
+    };
+    let storage = window().and_then(|window| window.local_storage().ok().flatten());
+    if storage.is_none_or(|storage| storage.set_item(INDEX_KEY, &json).is_err()) {
+        console::log_1(&"Couldn't save the cat cache index".into());
+    }
+}
+
+/// Wraps another source: fresh cats come from it while online, cached ones once the
+/// browser is offline or images keep failing.
+pub struct CachedSource {
+    inner: Box<dyn CatSource>,
+    cache: CatCache,
+    failures_in_a_row: u32,
+    /// Loaded cats to store, waiting for the effect runner
+    requests: Vec<SourceRequest>,
+}
+
+impl CachedSource {
+    pub fn new(inner: Box<dyn CatSource>, cache: CatCache) -> Self {
+        CachedSource { inner, cache, failures_in_a_row: 0, requests: Vec::new() }
+    }
+
+    fn offline(&self) -> bool {
+        let browser_offline = window().is_some_and(|window| !window.navigator().on_line());
+        browser_offline || self.failures_in_a_row >= OFFLINE_AFTER_FAILURES
+    }
+}
+
+impl CatSource for CachedSource {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn next_batch(&mut self, count: usize) -> Vec<String> {
+        if self.offline() {
+            let cached = self.cache.take(count);
+            if !cached.is_empty() {
+                console::log_1(&format!("Offline, showing {} cached cats", cached.len()).into());
+                return cached;
+            }
+        }
+        self.inner.next_batch(count)
+    }
+
+    fn image_loaded(&mut self, url: &str) {
+        self.failures_in_a_row = 0;
+        // Inline and already-cached images are local anyway
+        if !url.starts_with("data:") && !url.starts_with("blob:") {
+            self.requests.push(SourceRequest::Store(url.to_string()));
+        }
+    }
+
+    fn image_failed(&mut self, _url: &str) {
+        self.failures_in_a_row += 1;
+    }
+
+    fn metadata(&self, url: &str) -> Option<CatMetadata> {
+        self.inner.metadata(url)
+    }
+
+    fn link_id(&self, url: &str) -> Option<String> {
+        self.inner.link_id(url)
+    }
+
+    fn linked_cat(&mut self, id: &str) -> Option<String> {
+        self.inner.linked_cat(id)
+    }
+
+    fn query(&self) -> Option<&CatQuery> {
+        self.inner.query()
+    }
+
+    fn set_query(&mut self, query: CatQuery) {
+        self.inner.set_query(query);
+    }
+
+    fn take_requests(&mut self) -> Vec<SourceRequest> {
+        let mut requests = self.inner.take_requests();
+        requests.append(&mut self.requests);
+        requests
+    }
+
+    fn metadata_fetched(&mut self, generation: u32, metadata: Option<CatMetadata>) {
+        self.inner.metadata_fetched(generation, metadata);
+    }
+}
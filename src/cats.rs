@@ -0,0 +1,287 @@
+use sauron::wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use std::collections::VecDeque;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{console, window, HtmlImageElement, Response};
+
+pub use counter_core::cats::{
+    CatFilter, CatMetadata, CatQuery, CatSize, CatSource, CatWindow, FixtureSource, ImageSlot, ImageStatus,
+    LocalSource, PreloadSettings, SourceRequest,
+};
+
+use crate::platform::BrowserPlatform;
+use crate::router::Query;
+
+/// Read cataas metadata from the JSON it answers `?json=true` with
+fn metadata_from_json(value: &JsValue) -> Option<CatMetadata> {
+    let field = |name: &str| js_sys::Reflect::get(value, &name.into()).ok();
+    // Older cataas versions call it `_id`
+    let id = field("id")
+        .and_then(|id| id.as_string())
+        .or_else(|| field("_id").and_then(|id| id.as_string()))?;
+    let tags = field("tags")
+        .and_then(|tags| tags.dyn_into::<js_sys::Array>().ok())
+        .map(|tags| tags.iter().filter_map(|tag| tag.as_string()).collect())
+        .unwrap_or_default();
+    Some(CatMetadata { id, tags })
+}
+
+/// Cats whose metadata has arrived but that haven't been handed out yet
+const RESOLVE_AHEAD: usize = 20;
+
+/// Metadata kept for handed out cats, comfortably more than the preloader keeps in memory
+const METADATA_KEPT: usize = 100;
+
+/// Cats from cataas.com, or any server speaking its API.
+///
+/// Metadata for upcoming cats is fetched ahead with `?json=true`, so most cats are requested
+/// by id and their tags can be shown. When none is ready yet, a random cat is requested with
+/// a unique URL so the browser doesn't serve a cached cat.
+pub struct CataasSource {
+    base_url: String,
+    query: CatQuery,
+    issued: u64,
+    /// Cats whose metadata has arrived but that haven't been handed out yet
+    ready: VecDeque<CatMetadata>,
+    /// Metadata fetches asked for and not answered yet
+    in_flight: usize,
+    /// Bumped whenever the query changes, so answers to the old query are dropped
+    generation: u32,
+    requests: Vec<SourceRequest>,
+    /// Metadata of recently handed out cats, by image URL
+    metadata: VecDeque<(String, CatMetadata)>,
+}
+
+impl CataasSource {
+    pub fn new(base_url: &str) -> Self {
+        let mut source = CataasSource {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            query: CatQuery::default(),
+            issued: 0,
+            ready: VecDeque::new(),
+            in_flight: 0,
+            generation: 0,
+            requests: Vec::new(),
+            metadata: VecDeque::new(),
+        };
+        source.resolve_ahead();
+        source
+    }
+
+    fn remember(&mut self, url: String, metadata: CatMetadata) {
+        self.metadata.push_back((url, metadata));
+        if self.metadata.len() > METADATA_KEPT {
+            self.metadata.pop_front();
+        }
+    }
+
+    /// Ask for enough metadata to top up the queue of cats with known metadata
+    fn resolve_ahead(&mut self) {
+        let missing = RESOLVE_AHEAD.saturating_sub(self.ready.len() + self.in_flight);
+        let url = format!("{}{}", self.base_url, self.query.cataas_url(None, &[("json", "true".to_string())]));
+        for _ in 0..missing {
+            self.in_flight += 1;
+            self.requests.push(SourceRequest::FetchMetadata { url: url.clone(), generation: self.generation });
+        }
+    }
+}
+
+/// Fetch the metadata cataas answers a `?json=true` request with
+pub async fn fetch_metadata(url: &str) -> Result<CatMetadata, String> {
+    let fetched = async {
+        let window = window().ok_or("no window")?;
+        let response: Response = JsFuture::from(window.fetch_with_str(url)).await?.dyn_into()?;
+        if !response.ok() {
+            return Err(format!("HTTP {}", response.status()).into());
+        }
+        let json = JsFuture::from(response.json()?).await?;
+        metadata_from_json(&json).ok_or_else(|| JsValue::from("no id in cat metadata"))
+    };
+    fetched.await.map_err(|error: JsValue| format!("Couldn't fetch cat metadata: {:?}", error))
+}
+
+impl CatSource for CataasSource {
+    fn name(&self) -> &'static str {
+        "cataas"
+    }
+
+    fn next_batch(&mut self, count: usize) -> Vec<String> {
+        let now = js_sys::Date::now() as u64;
+        let urls = (0..count)
+            .map(|_| {
+                match self.ready.pop_front() {
+                    Some(metadata) => {
+                        let url = format!("{}{}", self.base_url, self.query.cataas_url(Some(&metadata.id), &[]));
+                        self.remember(url.clone(), metadata);
+                        url
+                    }
+                    None => {
+                        self.issued += 1;
+                        let unique = [("t", (now + self.issued).to_string())];
+                        format!("{}{}", self.base_url, self.query.cataas_url(None, &unique))
+                    }
+                }
+            })
+            .collect();
+        self.resolve_ahead();
+        urls
+    }
+
+    fn metadata(&self, url: &str) -> Option<CatMetadata> {
+        self.metadata.iter().find(|(known, _)| known == url).map(|(_, metadata)| metadata.clone())
+    }
+
+    /// Any cat cataas knows can be requested by its id; its tags are only known once
+    /// it comes up again through the resolver
+    fn linked_cat(&mut self, id: &str) -> Option<String> {
+        let url = format!("{}{}", self.base_url, self.query.cataas_url(Some(id), &[]));
+        self.remember(url.clone(), CatMetadata { id: id.to_string(), tags: Vec::new() });
+        Some(url)
+    }
+
+    fn query(&self) -> Option<&CatQuery> {
+        Some(&self.query)
+    }
+
+    fn set_query(&mut self, query: CatQuery) {
+        self.query = query;
+        self.ready.clear();
+        self.generation += 1;
+        self.resolve_ahead();
+    }
+
+    fn take_requests(&mut self) -> Vec<SourceRequest> {
+        std::mem::take(&mut self.requests)
+    }
+
+    fn metadata_fetched(&mut self, generation: u32, metadata: Option<CatMetadata>) {
+        self.in_flight = self.in_flight.saturating_sub(1);
+        match metadata {
+            Some(metadata) if generation == self.generation => self.ready.push_back(metadata),
+            _ => {}
+        }
+    }
+}
+
+/// A detached `<img>` being preloaded, owning its load callbacks.
+///
+/// Dropping it detaches the callbacks before freeing them and cancels the download,
+/// so evicted images don't leak and never call into freed closures.
+pub struct PreloadedImage {
+    element: HtmlImageElement,
+    _onload: Closure<dyn Fn()>,
+    _onerror: Closure<dyn Fn()>,
+}
+
+impl PreloadedImage {
+    pub fn new(element: HtmlImageElement, onload: Closure<dyn Fn()>, onerror: Closure<dyn Fn()>) -> Self {
+        element.set_onload(Some(onload.as_ref().unchecked_ref()));
+        element.set_onerror(Some(onerror.as_ref().unchecked_ref()));
+        PreloadedImage { element, _onload: onload, _onerror: onerror }
+    }
+
+    pub fn element(&self) -> &HtmlImageElement {
+        &self.element
+    }
+
+    pub fn start(&self, url: &str) {
+        self.element.set_src(url);
+    }
+}
+
+impl Drop for PreloadedImage {
+    fn drop(&mut self) {
+        self.element.set_onload(None);
+        self.element.set_onerror(None);
+        let _ = self.element.remove_attribute("src");
+    }
+}
+
+/// Read `<meta name="cat-window" content="behind=10, ahead=20">`
+pub fn cat_window_from_document() -> CatWindow {
+    let content = window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.query_selector("meta[name='cat-window']").ok().flatten())
+        .and_then(|meta| meta.get_attribute("content"))
+        .unwrap_or_default();
+    CatWindow::parse(&content, &BrowserPlatform)
+}
+
+const PRELOAD_SETTINGS_KEY: &str = "sauron-demo.preload";
+
+/// Saved preload settings, overridden by `?batch=`, `?look-ahead=`, `?concurrent=` and `?adaptive=on|off`
+pub fn preload_settings_from_document() -> PreloadSettings {
+    let mut settings = PreloadSettings::default();
+    let saved = storage()
+        .and_then(|storage| storage.get_item(PRELOAD_SETTINGS_KEY).ok().flatten())
+        .map(|saved| Query::parse(&saved))
+        .unwrap_or_default();
+    let search = window()
+        .and_then(|window| window.location().search().ok())
+        .map(|search| Query::parse(&search))
+        .unwrap_or_default();
+
+    for query in [saved, search] {
+        settings.apply_query(&query);
+    }
+    settings
+}
+
+pub fn save_preload_settings(settings: &PreloadSettings) {
+    let saved = storage().is_some_and(|storage| storage.set_item(PRELOAD_SETTINGS_KEY, &settings.to_query().to_string()).is_ok());
+    if !saved {
+        console::log_1(&"Couldn't save preload settings".into());
+    }
+}
+
+fn storage() -> Option<web_sys::Storage> {
+    window()?.local_storage().ok().flatten()
+}
+
+const DEFAULT_CATAAS_URL: &str = "https://cataas.com";
+
+/// Pick the cat source configured for this page.
+///
+/// `index.html` selects it with `<meta name="cat-source" content="cataas|local|fixture">`;
+/// `local` reads its files from a whitespace separated `data-files` attribute and `cataas`
+/// accepts a `data-url` to point at another server. `?cat-source=` and `?cataas-url=` query
+/// parameters override the meta tag, so CI can switch to `fixture` or a mock cataas server
+/// without editing the page.
+pub fn cat_source_from_document() -> Box<dyn CatSource> {
+    let meta = window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.query_selector("meta[name='cat-source']").ok().flatten());
+    let meta_attribute = |name: &str| meta.as_ref().and_then(|meta| meta.get_attribute(name));
+
+    let search = window()
+        .and_then(|window| window.location().search().ok())
+        .map(|search| Query::parse(&search))
+        .unwrap_or_default();
+    let choice = search.get("cat-source").or_else(|| meta_attribute("content")).unwrap_or_default();
+
+    let source: Box<dyn CatSource> = match choice.as_str() {
+        "fixture" => Box::new(FixtureSource::new()),
+        "local" => {
+            let files: Vec<String> = meta_attribute("data-files")
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(str::to_string)
+                .collect();
+            if files.is_empty() {
+                console::log_1(&"Local cat source has no data-files, using fixtures instead".into());
+                Box::new(FixtureSource::new())
+            } else {
+                Box::new(LocalSource::new(files))
+            }
+        }
+        _ => {
+            let base_url = search
+                .get("cataas-url")
+                .or_else(|| meta_attribute("data-url"))
+                .unwrap_or_else(|| DEFAULT_CATAAS_URL.to_string());
+            Box::new(CataasSource::new(&base_url))
+        }
+    };
+
+    console::log_1(&format!("Cat source: {}", source.name()).into());
+    source
+}
@@ -0,0 +1,205 @@
+use sauron::{Cmd, wasm_bindgen::closure::Closure, wasm_bindgen::JsCast};
+use std::future::ready;
+use web_sys::{console, window, HtmlImageElement};
+
+use crate::bridge::Bridge;
+use crate::cat_cache::CatCache;
+use crate::cats::{self, ImageSlot, PreloadSettings, PreloadedImage};
+use counter_core::cat_batches::ImageAction;
+use crate::favorites::{self, Favorite};
+use crate::lazy_images::LazyImages;
+use crate::router::{self, Route, Router};
+use crate::{share, slideshow, Msg};
+
+/// Something `App::update` wants done outside of its own state.
+///
+/// Update only changes the model and lists effects; an `EffectRunner` carries them out
+/// afterwards, in order. Anything an effect learns (a timer firing, an image loading, a
+/// save failing) comes back as a message.
+pub enum Effect {
+    Log(String),
+    /// Add a history entry for `route`
+    PushUrl(Route),
+    /// Point the current history entry at `route`
+    ReplaceUrl(Route),
+    /// Put the scroll position back to where it was when the current entry was left
+    RestoreScroll,
+    SetDocumentMeta(Route),
+    /// Ask the user a yes/no question, answered with `confirmed` or `cancelled`
+    Confirm { question: String, confirmed: Msg, cancelled: Option<Msg> },
+    /// Dispatch `msg` after `delay_ms` milliseconds
+    Timer { delay_ms: i32, msg: Msg },
+    /// Keep the preloading `<img>` elements in step with the cat bookkeeping
+    Images(ImageAction),
+    /// Favorite the current image at `index`, answered with `Msg::AddFavorite`
+    SnapshotFavorite { index: usize, url: String },
+    /// Store the favorites, answered with `Msg::FavoritesSaved`
+    SaveFavorites(Vec<Favorite>),
+    SavePreloadSettings(PreloadSettings),
+    /// Fetch cataas metadata from `url`, answered with `Msg::CatMetadataFetched`
+    FetchCatMetadata { url: String, generation: u32 },
+    /// Keep the loaded cat at this URL for offline browsing
+    CacheCat(String),
+    ClearCatCache,
+    /// Lazy-load grid images rendered by this update
+    ObserveLazyImages,
+    ToggleFullscreen,
+    /// Answered with `Msg::CatActionFinished`, as are copying and sharing
+    Download { url: String, name: String },
+    CopyImage { index: usize, url: String },
+    Share { url: String, name: String },
+}
+
+/// Carries out the effects of an update
+pub trait EffectRunner {
+    /// Run `effects` in order; the returned command resolves the ones that have to be awaited
+    fn run(&mut self, effects: Vec<Effect>) -> Cmd<Msg>;
+}
+
+/// Runs effects against the browser.
+///
+/// Owns the detached `<img>` elements doing the preloading, mirroring the app's current and
+/// next batch slot for slot, so their callbacks stay alive until the app lets go of the cat.
+/// Storage lives here too, so effects only ever carry plain data.
+pub struct BrowserEffects {
+    bridge: Bridge,
+    router: Router,
+    cat_cache: Option<CatCache>,
+    lazy_images: Option<LazyImages>,
+    current_images: Vec<Option<PreloadedImage>>,
+    next_batch_images: Vec<Option<PreloadedImage>>,
+}
+
+impl BrowserEffects {
+    pub fn new(bridge: Bridge, router: Router, cat_cache: Option<CatCache>, lazy_images: Option<LazyImages>) -> Self {
+        BrowserEffects {
+            bridge,
+            router,
+            cat_cache,
+            lazy_images,
+            current_images: Vec::new(),
+            next_batch_images: Vec::new(),
+        }
+    }
+
+    fn current_image(&self, index: usize) -> Option<&HtmlImageElement> {
+        self.current_images.get(index)?.as_ref().map(|image| image.element())
+    }
+
+    fn run_image_action(&mut self, action: ImageAction) {
+        match action {
+            ImageAction::Load { generation, slot, url } => {
+                let image = preload_image(&self.bridge, generation, slot, &url);
+                let (images, index) = match slot {
+                    ImageSlot::Current(index) => (&mut self.current_images, index),
+                    ImageSlot::NextBatch(index) => (&mut self.next_batch_images, index),
+                };
+                if let Some(held) = images.get_mut(index) {
+                    *held = image;
+                }
+            }
+            ImageAction::RetryAfter { generation, slot, delay_ms } => {
+                self.bridge.dispatch_after(delay_ms as i32, Msg::RetryImage(generation, slot));
+            }
+            ImageAction::Reset(count) => self.current_images = (0..count).map(|_| None).collect(),
+            ImageAction::Evict(count) => {
+                self.current_images.drain(..count.min(self.current_images.len()));
+            }
+            ImageAction::PrepareNextBatch(count) => self.next_batch_images = (0..count).map(|_| None).collect(),
+            ImageAction::CommitNextBatch => self.current_images.append(&mut self.next_batch_images),
+        }
+    }
+
+    fn run_one(&mut self, effect: Effect) -> Cmd<Msg> {
+        match effect {
+            Effect::Log(message) => console::log_1(&message.into()),
+            Effect::PushUrl(route) => self.router.navigate(&route),
+            Effect::ReplaceUrl(route) => self.router.replace(&route),
+            Effect::RestoreScroll => self.router.restore_scroll(),
+            Effect::SetDocumentMeta(route) => router::set_document_meta(&route),
+            Effect::Confirm { question, confirmed, cancelled } => {
+                let answer = if router::confirm(&question) { Some(confirmed) } else { cancelled };
+                if let Some(msg) = answer {
+                    return Cmd::new(ready(msg));
+                }
+            }
+            Effect::Timer { delay_ms, msg } => self.bridge.dispatch_after(delay_ms, msg),
+            Effect::Images(action) => self.run_image_action(action),
+            Effect::SnapshotFavorite { index, url } => {
+                let image = self.current_image(index)
+                    .and_then(favorites::snapshot)
+                    .unwrap_or_else(|| url.clone());
+                return Cmd::new(ready(Msg::AddFavorite(Favorite { source_url: url, image })));
+            }
+            Effect::SaveFavorites(items) => return Cmd::new(ready(Msg::FavoritesSaved(favorites::save(&items)))),
+            Effect::SavePreloadSettings(settings) => cats::save_preload_settings(&settings),
+            Effect::FetchCatMetadata { url, generation } => {
+                return Cmd::new(async move { Msg::CatMetadataFetched(generation, cats::fetch_metadata(&url).await) });
+            }
+            Effect::CacheCat(url) => {
+                if let Some(cache) = &self.cat_cache {
+                    cache.store(&url);
+                }
+            }
+            Effect::ClearCatCache => {
+                if let Some(cache) = &self.cat_cache {
+                    cache.clear();
+                }
+            }
+            Effect::ObserveLazyImages => {
+                if let Some(lazy_images) = &self.lazy_images {
+                    lazy_images.observe_after_render();
+                }
+            }
+            Effect::ToggleFullscreen => slideshow::toggle_fullscreen(),
+            Effect::Download { url, name } => {
+                return Cmd::new(async move { Msg::CatActionFinished(share::download(url, name).await) });
+            }
+            Effect::CopyImage { index, url } => {
+                let image = self.current_image(index).cloned();
+                return Cmd::new(async move { Msg::CatActionFinished(share::copy_image(image, url).await) });
+            }
+            Effect::Share { url, name } => {
+                return Cmd::new(async move { Msg::CatActionFinished(share::share(url, name).await) });
+            }
+        }
+        Cmd::none()
+    }
+}
+
+impl EffectRunner for BrowserEffects {
+    fn run(&mut self, effects: Vec<Effect>) -> Cmd<Msg> {
+        let cmds: Vec<Cmd<Msg>> = effects.into_iter().map(|effect| self.run_one(effect)).collect();
+        Cmd::batch(cmds)
+    }
+}
+
+/// Start loading `url` in a detached `<img>`, dispatching the loaded message for `slot`
+/// or `Msg::ImageFailed` once the browser is done with it
+fn preload_image(bridge: &Bridge, generation: u32, slot: ImageSlot, url: &str) -> Option<PreloadedImage> {
+    let document = window()?.document()?;
+    let img = document.create_element("img").ok()?.dyn_into::<HtmlImageElement>().ok()?;
+    // Lets favorites snapshot the image; the shown <img> uses the same mode so it hits the cache
+    img.set_cross_origin(Some("anonymous"));
+
+    let on_load = bridge.clone();
+    let url_clone = url.to_string();
+    let onload = Closure::wrap(Box::new(move || {
+        console::log_1(&format!("Image finished loading: {}", url_clone).into());
+        on_load.dispatch(match slot {
+            ImageSlot::Current(index) => Msg::ImageLoaded(generation, index),
+            ImageSlot::NextBatch(index) => Msg::NextBatchImageLoaded(generation, index),
+        });
+    }) as Box<dyn Fn()>);
+
+    let on_error = bridge.clone();
+    let url_clone = url.to_string();
+    let onerror = Closure::wrap(Box::new(move || {
+        console::log_1(&format!("Failed to load image: {}", url_clone).into());
+        on_error.dispatch(Msg::ImageFailed(generation, slot));
+    }) as Box<dyn Fn()>);
+
+    let image = PreloadedImage::new(img, onload, onerror);
+    image.start(url);
+    Some(image)
+}
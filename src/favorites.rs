@@ -0,0 +1,152 @@
+use sauron::wasm_bindgen::{JsCast, JsValue};
+use web_sys::{console, window, CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement, Storage};
+
+const STORAGE_KEY: &str = "sauron-demo.favorites";
+
+/// Longest side of the snapshot kept for each favorite, small enough for localStorage
+const SNAPSHOT_SIZE: u32 = 320;
+
+/// A cat the user decided to keep
+#[derive(Debug, Clone, PartialEq)]
+pub struct Favorite {
+    /// URL the cat was loaded from, used to recognise it in the browser
+    pub source_url: String,
+    /// What the gallery shows: a JPEG data URL snapshot, or `source_url` when no snapshot could be taken.
+    ///
+    /// Remote sources like cataas hand out a different cat for the same URL on every
+    /// request, so only the snapshot reliably brings the same cat back after a reload.
+    pub image: String,
+}
+
+/// Favorite cats, in the order the user arranged them.
+///
+/// Plain data; `load` and `save` keep it in localStorage.
+#[derive(Debug, Clone, Default)]
+pub struct Favorites {
+    items: Vec<Favorite>,
+}
+
+/// Read saved favorites; anything missing or malformed starts an empty list
+pub fn load() -> Favorites {
+    let saved = storage().and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten());
+    let Some(saved) = saved else {
+        return Favorites::default();
+    };
+
+    let items = js_sys::JSON::parse(&saved)
+        .ok()
+        .and_then(|value| value.dyn_into::<js_sys::Array>().ok())
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|entry| {
+                    let field = |name: &str| js_sys::Reflect::get(&entry, &name.into()).ok()?.as_string();
+                    Some(Favorite { source_url: field("url")?, image: field("image")? })
+                })
+                .collect()
+        })
+        .unwrap_or_else(|| {
+            console::log_1(&"Saved favorites are unreadable, starting over".into());
+            Vec::new()
+        });
+    Favorites { items }
+}
+
+/// Write `items` to localStorage, returning an error message when the browser refuses
+pub fn save(items: &[Favorite]) -> Result<(), String> {
+    let entries = js_sys::Array::new();
+    for favorite in items {
+        let entry = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&entry, &"url".into(), &favorite.source_url.as_str().into());
+        let _ = js_sys::Reflect::set(&entry, &"image".into(), &favorite.image.as_str().into());
+        entries.push(&entry);
+    }
+    let json = js_sys::JSON::stringify(&entries)
+        .ok()
+        .and_then(|json| json.as_string())
+        .ok_or("Couldn't encode favorites")?;
+
+    let storage = storage().ok_or("Local storage is not available")?;
+    storage
+        .set_item(STORAGE_KEY, &json)
+        .map_err(|_| "Couldn't save favorites, browser storage may be full".to_string())
+}
+
+impl Favorites {
+    pub fn items(&self) -> &[Favorite] {
+        &self.items
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn contains(&self, source_url: &str) -> bool {
+        self.items.iter().any(|favorite| favorite.source_url == source_url)
+    }
+
+    pub fn add(&mut self, favorite: Favorite) {
+        if !self.contains(&favorite.source_url) {
+            self.items.push(favorite);
+        }
+    }
+
+    pub fn remove_url(&mut self, source_url: &str) {
+        self.items.retain(|favorite| favorite.source_url != source_url);
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.items.len() {
+            self.items.remove(index);
+        }
+    }
+
+    /// Move the favorite at `from` to position `to`, shifting the ones in between
+    pub fn move_item(&mut self, from: usize, to: usize) {
+        if from < self.items.len() && to < self.items.len() {
+            let favorite = self.items.remove(from);
+            self.items.insert(to, favorite);
+        }
+    }
+}
+
+fn storage() -> Option<Storage> {
+    window()?.local_storage().ok().flatten()
+}
+
+/// Draw a loaded image into a small JPEG data URL.
+///
+/// Fails for cross-origin images served without CORS headers, since the canvas is then tainted.
+pub fn snapshot(image: &HtmlImageElement) -> Option<String> {
+    let (width, height) = (image.natural_width(), image.natural_height());
+    if !image.complete() || width == 0 || height == 0 {
+        return None;
+    }
+    let scale = (SNAPSHOT_SIZE as f64 / width.max(height) as f64).min(1.0);
+    let (width, height) = ((width as f64 * scale).round() as u32, (height as f64 * scale).round() as u32);
+
+    let canvas = window()?
+        .document()?
+        .create_element("canvas")
+        .ok()?
+        .dyn_into::<HtmlCanvasElement>()
+        .ok()?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context = canvas
+        .get_context("2d")
+        .ok()??
+        .dyn_into::<CanvasRenderingContext2d>()
+        .ok()?;
+    context
+        .draw_image_with_html_image_element_and_dw_and_dh(image, 0.0, 0.0, width as f64, height as f64)
+        .ok()?;
+
+    match canvas.to_data_url_with_type_and_encoder_options("image/jpeg", &JsValue::from_f64(0.85)) {
+        Ok(data_url) => Some(data_url),
+        Err(_) => {
+            console::log_1(&"Image can't be snapshotted (no CORS), keeping its URL instead".into());
+            None
+        }
+    }
+}
@@ -1,270 +1,25 @@
 use sauron::{html::text, node, Node, wasm_bindgen::JsCast};
-use web_sys::{console, HtmlInputElement};
+use web_sys::HtmlInputElement;
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum GameMsg {
-    RockPaperScissorsPlay(String),
-    GuessNumber(i32),
-    GuessInputChanged(String),
-    SubmitGuess,
-    NewGuessGame,
-    MemoryCardClick(usize),
-    StartMemoryGame,
-    ResetMemoryGame,
-    HideMemoryCards,
-}
+pub use counter_core::games::{
+    difficulty_from_query, seed_from_query, GameKind, GameMsg, GameState, DIFFICULTY_PARAM, SEED_PARAM,
+};
+use counter_core::rps::{Match, Outcome, Variant};
+use counter_core::guessing::{quick_picks, Difficulty};
+use counter_core::strategies::Strategy;
 
-#[derive(Debug, Clone)]
-pub struct GameState {
-    // Rock Paper Scissors
-    pub rps_player_choice: Option<String>,
-    pub rps_computer_choice: Option<String>,
-    pub rps_result: Option<String>,
-    pub rps_score: (i32, i32), // (player_wins, computer_wins)
-    
-    // Number Guessing
-    pub guess_target: i32,
-    pub guess_attempts: i32,
-    pub guess_message: String,
-    pub guess_game_over: bool,
-    pub guess_input: String,
-    
-    // Memory Cards
-    pub memory_cards: Vec<(String, bool, bool)>, // (emoji, revealed, matched)
-    pub memory_moves: i32,
-    pub memory_matches: i32,
-    pub memory_first_card: Option<usize>,
-    pub memory_game_over: bool,
-    pub memory_second_card: Option<usize>,
-    pub memory_showing_pair: bool,
-}
+/// Renders the games, which live in the browser-free core
+pub struct GameView<'a>(pub &'a GameState);
 
-impl GameState {
-    pub fn new() -> Self {
-        GameState {
-            rps_player_choice: None,
-            rps_computer_choice: None,
-            rps_result: None,
-            rps_score: (0, 0),
-            guess_target: Self::random_number(1, 100),
-            guess_attempts: 0,
-            guess_message: "Guess a number between 1 and 100!".to_string(),
-            guess_game_over: false,
-            guess_input: String::new(),
-            memory_cards: Vec::new(),
-            memory_moves: 0,
-            memory_matches: 0,
-            memory_first_card: None,
-            memory_game_over: false,
-            memory_second_card: None,
-            memory_showing_pair: false,
-        }
-    }
-    
-    fn random_number(min: i32, max: i32) -> i32 {
-        let range = max - min + 1;
-        let random_value = (js_sys::Math::random() * range as f64) as i32;
-        min + random_value
-    }
-    
-    fn get_rps_computer_choice() -> String {
-        let choices = ["rock", "paper", "scissors"];
-        let index = Self::random_number(0, 2) as usize;
-        choices[index].to_string()
-    }
-    
-    fn determine_rps_winner(player: &str, computer: &str) -> String {
-        match (player, computer) {
-            ("rock", "scissors") | ("paper", "rock") | ("scissors", "paper") => "You win!".to_string(),
-            ("scissors", "rock") | ("rock", "paper") | ("paper", "scissors") => "Computer wins!".to_string(),
-            _ => "It's a tie!".to_string(),
-        }
-    }
-    
-    fn initialize_memory_cards() -> Vec<(String, bool, bool)> {
-        let emojis = ["🐶", "🐱", "🐭", "🐹", "🐰", "🦊", "🐻", "🐼"];
-        let mut cards = Vec::new();
-        
-        // Add each emoji twice for pairs
-        for emoji in &emojis {
-            cards.push((emoji.to_string(), false, false));
-            cards.push((emoji.to_string(), false, false));
-        }
-        
-        // Shuffle the cards (simple shuffle using random swaps)
-        for i in 0..cards.len() {
-            let j = Self::random_number(0, (cards.len() - 1) as i32) as usize;
-            cards.swap(i, j);
-        }
-        
-        cards
-    }
-    
-    pub fn update(&mut self, msg: GameMsg) {
-        match msg {
-            GameMsg::RockPaperScissorsPlay(player_choice) => {
-                console::log_1(&format!("Player chose: {}", player_choice).into());
-                let computer_choice = Self::get_rps_computer_choice();
-                let result = Self::determine_rps_winner(&player_choice, &computer_choice);
-                
-                // Update score
-                if result.contains("You win") {
-                    self.rps_score.0 += 1;
-                } else if result.contains("Computer wins") {
-                    self.rps_score.1 += 1;
-                }
-                
-                self.rps_player_choice = Some(player_choice);
-                self.rps_computer_choice = Some(computer_choice);
-                self.rps_result = Some(result);
-            },
-            GameMsg::GuessInputChanged(value) => {
-                self.guess_input = value;
-            },
-            GameMsg::SubmitGuess => {
-                if let Ok(num) = self.guess_input.trim().parse::<i32>() {
-                    if num >= 1 && num <= 100 {
-                        console::log_1(&format!("Player guessed: {}", num).into());
-                        self.guess_attempts += 1;
-                        self.guess_input.clear();
-                        
-                        if num == self.guess_target {
-                            self.guess_message = format!("🎉 Correct! You found {} in {} attempts!", self.guess_target, self.guess_attempts);
-                            self.guess_game_over = true;
-                        } else if num < self.guess_target {
-                            self.guess_message = format!("📈 Too low! Try a higher number.");
-                        } else {
-                            self.guess_message = format!("📉 Too high! Try a lower number.");
-                        }
-                    } else {
-                        self.guess_input.clear();
-                        self.guess_message = "Please enter a number between 1 and 100!".to_string();
-                    }
-                } else {
-                    self.guess_input.clear();
-                    self.guess_message = "Please enter a valid number!".to_string();
-                }
-            },
-            GameMsg::GuessNumber(guess) => {
-                console::log_1(&format!("Player guessed: {}", guess).into());
-                self.guess_attempts += 1;
-                self.guess_input.clear();
-                
-                if guess == self.guess_target {
-                    self.guess_message = format!("🎉 Correct! You found {} in {} attempts!", self.guess_target, self.guess_attempts);
-                    self.guess_game_over = true;
-                } else if guess < self.guess_target {
-                    self.guess_message = format!("📈 Too low! Try a higher number.");
-                } else {
-                    self.guess_message = format!("📉 Too high! Try a lower number.");
-                }
-            },
-            GameMsg::NewGuessGame => {
-                console::log_1(&"Starting new guess game".into());
-                self.guess_target = Self::random_number(1, 100);
-                self.guess_attempts = 0;
-                self.guess_message = "Guess a number between 1 and 100!".to_string();
-                self.guess_game_over = false;
-                self.guess_input.clear();
-            },
-            GameMsg::StartMemoryGame => {
-                console::log_1(&"Starting memory game".into());
-                self.memory_cards = Self::initialize_memory_cards();
-                self.memory_moves = 0;
-                self.memory_matches = 0;
-                self.memory_first_card = None;
-                self.memory_game_over = false;
-                self.memory_second_card = None;
-                self.memory_showing_pair = false;
-            },
-            GameMsg::MemoryCardClick(index) => {
-                console::log_1(&format!("Memory card {} clicked", index).into());
-                
-                // Don't allow clicks if card is already revealed/matched
-                if self.memory_cards[index].1 || self.memory_cards[index].2 {
-                    return;
-                }
-                
-                // If we're showing a non-matching pair, hide them first
-                if self.memory_showing_pair {
-                    if let (Some(first_index), Some(second_index)) = (self.memory_first_card, self.memory_second_card) {
-                        if !self.memory_cards[first_index].2 && !self.memory_cards[second_index].2 {
-                            self.memory_cards[first_index].1 = false;
-                            self.memory_cards[second_index].1 = false;
-                        }
-                    }
-                    self.memory_first_card = None;
-                    self.memory_second_card = None;
-                    self.memory_showing_pair = false;
-                }
-                
-                // Reveal the clicked card
-                self.memory_cards[index].1 = true;
-                
-                match self.memory_first_card {
-                    None => {
-                        // First card of the pair
-                        self.memory_first_card = Some(index);
-                        console::log_1(&format!("First card selected: {}", index).into());
-                    },
-                    Some(first_index) => {
-                        // Second card of the pair
-                        self.memory_second_card = Some(index);
-                        self.memory_moves += 1;
-                        self.memory_showing_pair = true;
-                        
-                        console::log_1(&format!("Second card selected: {}, checking match...", index).into());
-                        
-                        if self.memory_cards[first_index].0 == self.memory_cards[index].0 {
-                            // Match found!
-                            console::log_1(&"Match found!".into());
-                            self.memory_cards[first_index].2 = true;
-                            self.memory_cards[index].2 = true;
-                            self.memory_matches += 1;
-                            
-                            // Reset for next turn
-                            self.memory_first_card = None;
-                            self.memory_second_card = None;
-                            self.memory_showing_pair = false;
-                            
-                            // Check if game is complete
-                            if self.memory_matches >= 8 {
-                                self.memory_game_over = true;
-                                console::log_1(&"Game completed!".into());
-                            }
-                        } else {
-                            // No match - cards will be hidden when next card is clicked
-                            console::log_1(&"No match, cards will be hidden shortly".into());
-                        }
-                    }
-                }
-            },
-            GameMsg::HideMemoryCards => {
-                console::log_1(&"Hiding non-matching cards".into());
-                if let (Some(first_index), Some(second_index)) = (self.memory_first_card, self.memory_second_card) {
-                    if !self.memory_cards[first_index].2 && !self.memory_cards[second_index].2 {
-                        self.memory_cards[first_index].1 = false;
-                        self.memory_cards[second_index].1 = false;
-                    }
-                }
-                
-                self.memory_first_card = None;
-                self.memory_second_card = None;
-                self.memory_showing_pair = false;
-            },
-            GameMsg::ResetMemoryGame => {
-                console::log_1(&"Resetting memory game".into());
-                self.memory_cards.clear();
-                self.memory_moves = 0;
-                self.memory_matches = 0;
-                self.memory_first_card = None;
-                self.memory_game_over = false;
-                self.memory_second_card = None;
-                self.memory_showing_pair = false;
-            },
-        }
+impl std::ops::Deref for GameView<'_> {
+    type Target = GameState;
+
+    fn deref(&self) -> &GameState {
+        self.0
     }
-    
+}
+
+impl GameView<'_> {
     pub fn render_games_page<MSG>(&self) -> Node<MSG> 
     where 
         MSG: From<GameMsg> + 'static
@@ -274,6 +29,7 @@ impl GameState {
                 <h2 class="text-center mb-8 text-gray-800">
                     {text("Mini Games Collection")}
                 </h2>
+                {self.render_seed_controls()}
                 
                 <div class="grid grid-cols-1 lg:grid-cols-2 xl:grid-cols-3 gap-5 max-w-6xl mx-auto">
                     {self.render_rock_paper_scissors()}
@@ -284,52 +40,133 @@ impl GameState {
         }
     }
     
+    pub fn render_game<MSG>(&self, kind: GameKind) -> Node<MSG> 
+    where 
+        MSG: From<GameMsg> + 'static
+    {
+        match kind {
+            GameKind::RockPaperScissors => self.render_rock_paper_scissors(),
+            GameKind::NumberGuessing => self.render_number_guessing(),
+            GameKind::Memory => self.render_memory_game(),
+        }
+    }
+    
+    /// The seed every game draws from, with a way to replay one or roll a new one
+    pub fn render_seed_controls<MSG>(&self) -> Node<MSG> 
+    where 
+        MSG: From<GameMsg> + 'static
+    {
+        node! {
+            <div class="flex gap-2 justify-center items-center mb-4 flex-wrap text-sm text-gray-600">
+                <label for="game-seed">{text("Seed:")}</label>
+                <input id="game-seed"
+                    type="number"
+                    min="0"
+                    value={&self.seed_input}
+                    class="p-1 border border-gray-300 rounded w-28 text-center"
+                    on_input=|event| {
+                        let value = event.event.target()
+                            .and_then(|target| target.dyn_into::<HtmlInputElement>().ok())
+                            .map(|input| input.value())
+                            .unwrap_or_default();
+                        MSG::from(GameMsg::SeedInputChanged(value))
+                    } />
+                <button class="small-button"
+                    disabled={self.seed_input.trim() == self.seed.to_string()}
+                    title="Restart every game from this seed"
+                    on_click=|_| MSG::from(GameMsg::ApplySeed)>
+                    {text("Replay")}
+                </button>
+                <button class="small-button" title="Restart every game from a new seed" on_click=|_| MSG::from(GameMsg::NewSeed)>
+                    {text("🎲 New seed")}
+                </button>
+            </div>
+        }
+    }
+    
     fn render_rock_paper_scissors<MSG>(&self) -> Node<MSG> 
     where 
         MSG: From<GameMsg> + 'static
     {
+        let variant = self.rps_variant;
+        let score = self.rps_score();
+        let match_over = self.rps_match.is_some_and(|current| current.is_over());
+        let strategy = self.rps_strategy;
+        let strategy_record = self.rps_strategy_record();
         node! {
             <div class="game-card">
                 <h3 class="text-center mb-5 text-blue-600">
                     {text("🪨 Rock Paper Scissors ✂️")}
                 </h3>
                 <div class="text-center mb-4">
+                    <select class="px-2 py-1 border border-gray-300 rounded-md text-sm mb-2.5"
+                        on_change=|event| MSG::from(GameMsg::SelectRpsVariant(Variant::from_slug(&event.value())))>
+                        {for choice in Variant::choices() {
+                            node! {
+                                <option value={choice.slug()} selected={choice == variant}>
+                                    {text(choice.label())}
+                                </option>
+                            }
+                        }}
+                    </select>
+                    {
+                        if let Variant::Cyclic(count) = variant {
+                            node! {
+                                <div class="text-sm text-gray-600 mb-2.5">
+                                    {text(format!("Each move beats the {} moves before it, wrapping around", (count - 1) / 2))}
+                                </div>
+                            }
+                        } else {
+                            text("")
+                        }
+                    }
+                    <label class="flex gap-2 justify-center items-center text-sm text-gray-600 mb-2.5">
+                        {text("Computer plays:")}
+                        <select class="px-2 py-1 border border-gray-300 rounded-md"
+                            on_change=|event| MSG::from(GameMsg::SelectRpsStrategy(Strategy::from_slug(&event.value())))>
+                            {for choice in Strategy::ALL {
+                                node! {
+                                    <option value={choice.slug()} selected={choice == strategy}>
+                                        {text(choice.label())}
+                                    </option>
+                                }
+                            }}
+                        </select>
+                    </label>
+                    <div class="text-sm text-gray-600 mb-2.5">
+                        {text(match strategy_record.win_rate() {
+                            Some(rate) => format!("{} has won {:.0}% of {} rounds against you", strategy.label(), rate * 100.0, strategy_record.rounds()),
+                            None => format!("{} hasn't played you yet", strategy.label()),
+                        })}
+                    </div>
                     <div class="text-lg mb-2.5">
-                        {text(format!("Score: You {} - {} Computer", self.rps_score.0, self.rps_score.1))}
+                        {text(format!("Score: You {} - {} Computer, {} tied", score.wins, score.losses, score.ties))}
                     </div>
+                    {self.render_rps_match()}
                     <div class="flex gap-2 justify-center mb-4 flex-wrap">
-                        <button class="game-button" on_click=|_| MSG::from(GameMsg::RockPaperScissorsPlay("rock".to_string()))>
-                            {text("🪨 Rock")}
-                        </button>
-                        <button class="game-button" on_click=|_| MSG::from(GameMsg::RockPaperScissorsPlay("paper".to_string()))>
-                            {text("📄 Paper")}
-                        </button>
-                        <button class="game-button" on_click=|_| MSG::from(GameMsg::RockPaperScissorsPlay("scissors".to_string()))>
-                            {text("✂️ Scissors")}
-                        </button>
+                        {for player_move in variant.moves() {
+                            node! {
+                                <button class="game-button" disabled={match_over} on_click=move |_| MSG::from(GameMsg::RockPaperScissorsPlay(player_move))>
+                                    {text(player_move.to_string())}
+                                </button>
+                            }
+                        }}
                     </div>
                     {
-                        if let (Some(player), Some(computer), Some(result)) = (&self.rps_player_choice, &self.rps_computer_choice, &self.rps_result) {
+                        if let Some(round) = self.rps_last_round() {
                             node! {
                                 <div class="bg-white/80 p-4 rounded-lg">
                                     <div class="mb-2.5">
-                                        {text(format!("You played: {}", match player.as_str() {
-                                            "rock" => "🪨 Rock",
-                                            "paper" => "📄 Paper",
-                                            "scissors" => "✂️ Scissors",
-                                            _ => player
-                                        }))}
+                                        {text(format!("You played: {}", round.player))}
                                     </div>
                                     <div class="mb-2.5">
-                                        {text(format!("Computer played: {}", match computer.as_str() {
-                                            "rock" => "🪨 Rock",
-                                            "paper" => "📄 Paper",
-                                            "scissors" => "✂️ Scissors",
-                                            _ => computer
-                                        }))}
+                                        {text(format!("Computer played: {}", round.computer))}
+                                    </div>
+                                    <div class="mb-2.5 text-gray-600">
+                                        {text(variant.describe(round.player, round.computer))}
                                     </div>
                                     <div class="font-bold text-blue-600">
-                                        {text(result)}
+                                        {text(round.outcome.message())}
                                     </div>
                                 </div>
                             }
@@ -341,39 +178,140 @@ impl GameState {
                             }
                         }
                     }
+                    {self.render_rps_history()}
+                    <button class="small-button mt-4" on_click=|_| MSG::from(GameMsg::ResetRps)>
+                        {text("🔄 Reset scores")}
+                    </button>
                 </div>
             </div>
         }
     }
     
+    /// Match length picker and how the current match stands
+    fn render_rps_match<MSG>(&self) -> Node<MSG> 
+    where 
+        MSG: From<GameMsg> + 'static
+    {
+        let best_of = self.rps_match.map(|current| current.best_of);
+        node! {
+            <div class="mb-4">
+                <select class="px-2 py-1 border border-gray-300 rounded-md text-sm mb-2.5"
+                    on_change=|event| MSG::from(GameMsg::SelectRpsMatchLength(event.value().parse().ok()))>
+                    <option value="free" selected={best_of.is_none()}>{text("Free play")}</option>
+                    {for length in Match::LENGTHS {
+                        node! {
+                            <option value={length.to_string()} selected={best_of == Some(length)}>
+                                {text(format!("Best of {}", length))}
+                            </option>
+                        }
+                    }}
+                </select>
+                {
+                    match self.rps_match {
+                        Some(current) => match current.winner() {
+                            Some(winner) => node! {
+                                <div class="font-bold text-blue-600">
+                                    <div class="mb-2.5">
+                                        {text(match winner {
+                                            Outcome::Win => format!("🏆 You won the match {}-{}!", current.record.wins, current.record.losses),
+                                            _ => format!("💻 The computer won the match {}-{}", current.record.losses, current.record.wins),
+                                        })}
+                                    </div>
+                                    <button class="game-button" on_click=|_| MSG::from(GameMsg::NewRpsMatch)>
+                                        {text("New match")}
+                                    </button>
+                                </div>
+                            },
+                            None => node! {
+                                <div class="text-sm text-gray-600">
+                                    {text(format!(
+                                        "Match: You {} - {} Computer, {} tied (first to {})",
+                                        current.record.wins, current.record.losses, current.record.ties, current.wins_needed()
+                                    ))}
+                                </div>
+                            },
+                        },
+                        None => text(""),
+                    }
+                }
+            </div>
+        }
+    }
+    
+    /// Every round since the last reset, newest first
+    fn render_rps_history<MSG>(&self) -> Node<MSG> 
+    where 
+        MSG: From<GameMsg> + 'static
+    {
+        if self.rps_rounds.is_empty() {
+            return text("");
+        }
+        node! {
+            <ol class="mt-4 max-h-40 overflow-y-auto text-sm text-left bg-white/60 rounded-lg p-2">
+                {for (number, round) in self.rps_rounds.iter().enumerate().rev() {
+                    node! {
+                        <li class="py-0.5">
+                            {text(format!("#{} {} vs {}: {}", number + 1, round.player, round.computer, round.outcome.message()))}
+                        </li>
+                    }
+                }}
+            </ol>
+        }
+    }
+    
     fn render_number_guessing<MSG>(&self) -> Node<MSG> 
     where 
         MSG: From<GameMsg> + 'static
     {
-        
+        let rules = self.guess_rules;
         node! {
             <div class="game-card">
                 <h3 class="text-center mb-5 text-blue-600">
                     {text("🎯 Number Guessing Game")}
                 </h3>
                 <div class="text-center">
+                    {self.render_guess_settings()}
                     <div class="mb-4">
-                        {text(format!("Attempts: {}", self.guess_attempts))}
+                        {text(match rules.max_attempts {
+                            Some(limit) => format!("Attempts: {} of {}", self.guess_attempts, limit),
+                            None => format!("Attempts: {}", self.guess_attempts),
+                        })}
                     </div>
                     <div class="mb-4 text-gray-600">
                         {text(&self.guess_message)}
                     </div>
+                    {
+                        match &self.guess_warning {
+                            Some(warning) => node! { <div class="mb-4 text-amber-600 text-sm">{text(warning)}</div> },
+                            None => text(""),
+                        }
+                    }
+                    {
+                        if !self.guess_game_over && self.guess_attempts > 0 {
+                            node! {
+                                <div class="mb-4 text-sm text-gray-600">
+                                    {text(if self.guess_low == self.guess_high {
+                                        format!("It can only be {} now", self.guess_low)
+                                    } else {
+                                        format!("It's between {} and {}", self.guess_low, self.guess_high)
+                                    })}
+                                </div>
+                            }
+                        } else {
+                            text("")
+                        }
+                    }
                     {
                         if !self.guess_game_over {
                             node! {
                                 <div>
                                     <div class="flex gap-2 justify-center items-center mb-4 flex-wrap">
                                         <input type="number" 
-                                            min="1" 
-                                            max="100" 
-                                            placeholder="1-100" 
+                                            min={rules.min.to_string()} 
+                                            max={rules.max.to_string()} 
+                                            placeholder={format!("{}-{}", rules.min, rules.max)} 
                                             value={&self.guess_input}
-                                            class="p-2 border border-gray-300 rounded w-20 text-center"
+                                            class="p-2 border border-gray-300 rounded w-28 text-center"
                                             on_input=|event| {
                                                 if let Some(input) = event.event.target() {
                                                     if let Ok(input_element) = input.dyn_into::<HtmlInputElement>() {
@@ -395,19 +333,26 @@ impl GameState {
                                         {text("Quick options:")}
                                     </div>
                                     <div class="flex gap-1.5 justify-center flex-wrap">
-                                        <button class="small-button" on_click=|_| MSG::from(GameMsg::GuessNumber(25))>{text("25")}</button>
-                                        <button class="small-button" on_click=|_| MSG::from(GameMsg::GuessNumber(50))>{text("50")}</button>
-                                        <button class="small-button" on_click=|_| MSG::from(GameMsg::GuessNumber(75))>{text("75")}</button>
-                                        <button class="small-button" on_click=|_| MSG::from(GameMsg::GuessNumber(1))>{text("1")}</button>
-                                        <button class="small-button" on_click=|_| MSG::from(GameMsg::GuessNumber(100))>{text("100")}</button>
+                                        {for pick in quick_picks(self.guess_low, self.guess_high) {
+                                            node! {
+                                                <button class="small-button" on_click=move |_| MSG::from(GameMsg::GuessNumber(pick))>
+                                                    {text(pick.to_string())}
+                                                </button>
+                                            }
+                                        }}
                                     </div>
                                 </div>
                             }
                         } else {
                             node! {
-                                <button class="game-button" on_click=|_| MSG::from(GameMsg::NewGuessGame)>
-                                    {text("🎯 New Game")}
-                                </button>
+                                <div>
+                                    <div class="mb-4 text-sm text-gray-600">
+                                        {text(self.guess_analysis().unwrap_or_default())}
+                                    </div>
+                                    <button class="game-button" on_click=|_| MSG::from(GameMsg::NewGuessGame)>
+                                        {text("🎯 New Game")}
+                                    </button>
+                                </div>
                             }
                         }
                     }
@@ -416,6 +361,57 @@ impl GameState {
         }
     }
     
+    /// Difficulty picker, with the custom range and guess limit when playing by custom rules
+    fn render_guess_settings<MSG>(&self) -> Node<MSG> 
+    where 
+        MSG: From<GameMsg> + 'static
+    {
+        let difficulty = self.guess_difficulty;
+        node! {
+            <div class="mb-4 text-sm text-gray-600">
+                <select class="px-2 py-1 border border-gray-300 rounded-md mb-2"
+                    on_change=|event| MSG::from(GameMsg::SelectGuessDifficulty(Difficulty::from_slug(&event.value())))>
+                    {for choice in Difficulty::ALL {
+                        node! {
+                            <option value={choice.slug()} selected={choice == difficulty}>
+                                {text(match choice.rules() {
+                                    Some(rules) => format!("{}: {}", choice.label(), rules.describe()),
+                                    None => choice.label().to_string(),
+                                })}
+                            </option>
+                        }
+                    }}
+                </select>
+                {
+                    if difficulty == Difficulty::Custom {
+                        node! {
+                            <div class="flex gap-2 justify-center items-center flex-wrap">
+                                <input type="number" aria-label="Lowest number"
+                                    value={&self.guess_custom_min}
+                                    class="p-1 border border-gray-300 rounded w-20 text-center"
+                                    on_input=|event| MSG::from(GameMsg::GuessCustomMinChanged(event.value())) />
+                                {text("to")}
+                                <input type="number" aria-label="Highest number"
+                                    value={&self.guess_custom_max}
+                                    class="p-1 border border-gray-300 rounded w-20 text-center"
+                                    on_input=|event| MSG::from(GameMsg::GuessCustomMaxChanged(event.value())) />
+                                <input type="number" min="1" aria-label="Guess limit" placeholder="No limit"
+                                    value={&self.guess_custom_attempts}
+                                    class="p-1 border border-gray-300 rounded w-24 text-center"
+                                    on_input=|event| MSG::from(GameMsg::GuessCustomAttemptsChanged(event.value())) />
+                                <button class="small-button" on_click=|_| MSG::from(GameMsg::ApplyGuessCustom)>
+                                    {text("Play")}
+                                </button>
+                            </div>
+                        }
+                    } else {
+                        text("")
+                    }
+                }
+            </div>
+        }
+    }
+    
     fn render_memory_game<MSG>(&self) -> Node<MSG> 
     where 
         MSG: From<GameMsg> + 'static
@@ -0,0 +1,74 @@
+use sauron::wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{console, window, IntersectionObserver, IntersectionObserverEntry, IntersectionObserverInit};
+
+/// Images still waiting to be loaded: rendered with `data-src` and no `src`
+const PENDING_IMAGES: &str = "img[data-src]:not([src])";
+
+/// Start loading images this far before they scroll into view
+const ROOT_MARGIN: &str = "200px";
+
+/// Loads `<img data-src="...">` elements only once they come close to the viewport.
+///
+/// The view renders lazy images without a `src`; after each render `observe_after_render`
+/// picks up the new ones, and the observer copies `data-src` into `src` when they appear.
+pub struct LazyImages {
+    observer: IntersectionObserver,
+    _callback: Closure<dyn Fn(js_sys::Array, IntersectionObserver)>,
+}
+
+impl LazyImages {
+    pub fn new() -> Option<Self> {
+        let callback = Closure::wrap(Box::new(|entries: js_sys::Array, observer: IntersectionObserver| {
+            for entry in entries.iter() {
+                let Ok(entry) = entry.dyn_into::<IntersectionObserverEntry>() else { continue };
+                if !entry.is_intersecting() {
+                    continue;
+                }
+                let image = entry.target();
+                if let Some(src) = image.get_attribute("data-src") {
+                    let _ = image.set_attribute("src", &src);
+                }
+                observer.unobserve(&image);
+            }
+        }) as Box<dyn Fn(js_sys::Array, IntersectionObserver)>);
+
+        let options = IntersectionObserverInit::new();
+        options.set_root_margin(ROOT_MARGIN);
+        match IntersectionObserver::new_with_options(callback.as_ref().unchecked_ref(), &options) {
+            Ok(observer) => Some(LazyImages { observer, _callback: callback }),
+            Err(_) => {
+                console::log_1(&"IntersectionObserver is not supported, images load eagerly".into());
+                None
+            }
+        }
+    }
+
+    /// Watch every pending lazy image once the render queued by the current update is done
+    pub fn observe_after_render(&self) {
+        let Some(window) = window() else {
+            return;
+        };
+        let observer = self.observer.clone();
+        let callback = Closure::once_into_js(move || {
+            let Some(images) = web_sys::window()
+                .and_then(|window| window.document())
+                .and_then(|document| document.query_selector_all(PENDING_IMAGES).ok())
+            else {
+                return;
+            };
+            for index in 0..images.length() {
+                if let Some(image) = images.item(index).and_then(|node| node.dyn_into::<web_sys::Element>().ok()) {
+                    // Observing an element twice is a no-op, so already watched images are fine
+                    observer.observe(&image);
+                }
+            }
+        });
+        let _ = window.request_animation_frame(callback.unchecked_ref());
+    }
+}
+
+impl Drop for LazyImages {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
@@ -2,45 +2,35 @@ use sauron::{
     Application, Cmd, Node, Program, html::text, node, wasm_bindgen, 
     web_sys::console,
 };
-use web_sys::{HtmlImageElement, window};
-use wasm_bindgen::closure::Closure;
+use web_sys::window;
 use wasm_bindgen::JsCast;
 
+mod bridge;
+mod cat_cache;
+mod cats;
+mod effects;
+mod favorites;
 mod games;
+mod lazy_images;
+mod platform;
+mod router;
+mod share;
+mod slideshow;
+#[cfg(test)]
+mod tests;
 
-#[derive(Debug, Clone, PartialEq)]
-enum Route {
-    Home,
-    Counter,
-    Cats,
-    Games,
-    About,
-    NotFound,
-}
-
-impl Route {
-    fn from_hash(hash: &str) -> Self {
-        match hash {
-            "#/" | "#" | "" => Route::Home,
-            "#/counter" => Route::Counter,
-            "#/cats" => Route::Cats,
-            "#/games" => Route::Games,
-            "#/about" => Route::About,
-            _ => Route::NotFound,
-        }
-    }
-    
-    fn to_hash(&self) -> &'static str {
-        match self {
-            Route::Home => "#/",
-            Route::Counter => "#/counter",
-            Route::Cats => "#/cats",
-            Route::Games => "#/games",
-            Route::About => "#/about",
-            Route::NotFound => "#/404",
-        }
-    }
-}
+use bridge::Bridge;
+use cat_cache::{CachedSource, CatCache};
+use effects::{BrowserEffects, Effect, EffectRunner};
+use favorites::{Favorite, Favorites};
+use lazy_images::LazyImages;
+use cats::{CatFilter, CatMetadata, CatQuery, CatSize, ImageSlot, ImageStatus, PreloadSettings, SourceRequest};
+use counter_core::cat_batches::CatBatches;
+use counter_core::counter::Counter;
+use games::GameView;
+use platform::{BrowserPlatform, Platform};
+use router::{Query, Route, RouteHooks, Router};
+use slideshow::Slideshow;
 
 enum Msg {
     Increment,
@@ -51,12 +41,60 @@ enum Msg {
     Panic,
     DivideByZero,
     FetchCat,
-    ImageLoaded(usize), // Index of the image that loaded
-    AllImagesLoaded,
-    NextBatchImageLoaded,
-    NextBatchComplete,
+    NextCat,
+    PreviousCat,
+    CatJumpInputChanged(String),
+    SubmitCatJump,
+    SwipeStarted(f64),
+    SwipeEnded(f64),
+    SetCatView(CatView),
+    ShowCatFromGrid(usize),
+    LoadMoreCats,
+    ToggleCatSearch,
+    CatTagChanged(String),
+    CatSaysChanged(String),
+    CatFilterChanged(String),
+    CatSizeChanged(String),
+    ApplyCatQuery,
+    ResetCatQuery,
+    ToggleFavorite,
+    /// A favorite with its snapshot taken, ready to be added
+    AddFavorite(Favorite),
+    FavoritesSaved(Result<(), String>),
+    RemoveFavorite(usize),
+    MoveFavorite(usize, usize),
+    /// Metadata asked for by the cat source, with the generation it was asked for in
+    CatMetadataFetched(u32, Result<CatMetadata, String>),
+    ClearCatCache,
+    BatchSizeChanged(String),
+    LookAheadChanged(String),
+    MaxConcurrentChanged(String),
+    ToggleAdaptivePreload,
+    ResetPreloadSettings,
+    ToggleAutoplay,
+    AutoplayIntervalChanged(String),
+    /// Carries the slideshow generation it was scheduled for
+    AutoplayTick(u32),
+    StageHovered(bool),
+    StageFocused(bool),
+    ToggleFullscreen,
+    FullscreenChanged(bool),
+    DownloadCat,
+    CopyCat,
+    ShareCat,
+    /// What came of a download, copy or share, for the user
+    CatActionFinished(String),
+    // Each carries the `CatBatches::generation` the image was started in
+    ImageLoaded(u32, usize), // Index of the image that loaded
+    NextBatchImageLoaded(u32, usize),
+    ImageFailed(u32, ImageSlot),
+    RetryImage(u32, ImageSlot),
     NavigateTo(Route),
     UrlChanged(Route),
+    /// Move to a route the leave guard has let through
+    ChangeRoute(Route, Navigation),
+    /// The user chose to stay when asked by the leave guard
+    NavigationCancelled(Navigation),
     // Games messages
     Game(games::GameMsg),
 }
@@ -67,223 +105,304 @@ impl From<games::GameMsg> for Msg {
     }
 }
 
+/// How a route change started, which decides what happens to the URL
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Navigation {
+    /// A link or button in the app; the URL follows the route
+    Link,
+    /// Back/forward or an edited URL; the browser already moved the URL
+    History,
+}
+
+/// How the cat browser lays out its cats
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CatView {
+    Single,
+    Grid,
+}
+
+/// Value of `name` in the page's query string
+fn query_param(name: &str) -> Option<String> {
+    let search = window()?.location().search().ok()?;
+    Query::parse(&search).get(name)
+}
+
+/// Horizontal travel needed for a touch to count as a swipe in the cat browser
+const SWIPE_THRESHOLD_PX: f64 = 50.0;
+
 struct App {
-    count: f64,
-    cat_urls: Vec<String>,
-    current_cat_index: usize,
-    cat_loading: bool,
-    preloaded_images: Vec<Option<HtmlImageElement>>,
-    images_loaded_count: usize,
-    is_preloading_next_batch: bool,
-    total_cats_ever_loaded: usize,
-    next_batch_urls: Vec<String>,
-    next_batch_loaded_count: usize,
+    counter: Counter,
+    /// Randomness, time and logging for the core
+    platform: Box<dyn Platform>,
+    cats: CatBatches,
+    /// Offline copies of loaded cats, unless disabled or unsupported
+    cat_cache: Option<CatCache>,
+    cat_jump_input: String,
+    swipe_start_x: Option<f64>,
+    cat_view: CatView,
+    slideshow: Slideshow,
+    fullscreen: bool,
+    /// Cat shown before the current one, kept underneath while the new one fades in
+    previous_cat_url: Option<String>,
+    /// Outcome of the last download, copy or share of the current cat
+    cat_action_status: Option<String>,
+    show_cat_search: bool,
+    /// Search being edited in the panel, handed to the source once applied
+    cat_query_draft: CatQuery,
+    /// Grid images load as they scroll into view; false where IntersectionObserver is missing
+    lazy_images: bool,
+    favorites: Favorites,
+    /// Why the last change to favorites couldn't be saved
+    favorites_error: Option<String>,
     current_route: Route,
+    /// Effects queued by the current update
+    effects: Vec<Effect>,
+    runner: Box<dyn EffectRunner>,
     // Games state
     games: games::GameState,
 }
 
 impl App {
-    fn new() -> Self {
-        let initial_route = Self::get_current_route();
-        let mut app = App { 
-            count: 0.0, 
-            cat_urls: Vec::new(), 
-            current_cat_index: 0, 
-            cat_loading: false,
-            preloaded_images: Vec::new(),
-            images_loaded_count: 0,
-            is_preloading_next_batch: false,
-            total_cats_ever_loaded: 0,
-            next_batch_urls: Vec::new(),
-            next_batch_loaded_count: 0,
-            current_route: initial_route.clone(),
-            // Games state initialization
-            games: games::GameState::new(),
+    /// An app starting at `initial_route` with nothing saved, that leaves randomness, time and
+    /// logging to `platform` and its effects to `runner`
+    fn new(mut platform: Box<dyn Platform>, runner: Box<dyn EffectRunner>, cats: CatBatches, initial_route: Route) -> Self {
+        let games = games::GameState::new(counter_core::rng::fresh_seed(&mut *platform));
+        App { 
+            counter: Counter::default(),
+            platform,
+            cats,
+            cat_cache: None,
+            cat_jump_input: String::new(),
+            swipe_start_x: None,
+            cat_view: CatView::Single,
+            slideshow: Slideshow::from_query(None),
+            fullscreen: false,
+            previous_cat_url: None,
+            cat_action_status: None,
+            show_cat_search: false,
+            cat_query_draft: CatQuery::default(),
+            lazy_images: false,
+            favorites: Favorites::default(),
+            favorites_error: None,
+            current_route: initial_route,
+            effects: Vec::new(),
+            runner,
+            games,
+        }
+    }
+    
+    /// The app as configured by the page it runs in, with effects carried out by the browser
+    fn from_document(bridge: Bridge, router: Router) -> Self {
+        let initial_route = router.current_route();
+        let cat_cache = CatCache::from_document();
+        let cat_source = match &cat_cache {
+            Some(cache) => Box::new(CachedSource::new(cats::cat_source_from_document(), cache.clone())),
+            None => cats::cat_source_from_document(),
         };
-        
-        // Auto-load cats if starting on cats page
-        if initial_route == Route::Cats {
-            console::log_1(&"Auto-loading cats on app initialization".into());
-            app.init_cat_loading();
+        let cats = CatBatches::new(cat_source, cats::cat_window_from_document(), cats::preload_settings_from_document());
+        let lazy_images = LazyImages::new();
+        let has_lazy_images = lazy_images.is_some();
+        let runner = BrowserEffects::new(bridge, router, cat_cache.clone(), lazy_images);
+        App {
+            cat_cache,
+            slideshow: Slideshow::from_query(query_param("autoplay")),
+            lazy_images: has_lazy_images,
+            favorites: favorites::load(),
+            ..App::new(Box::new(BrowserPlatform), Box::new(runner), cats, initial_route)
         }
-        
-        app
     }
     
-    fn get_current_route() -> Route {
-        if let Some(window) = window() {
-            let location = window.location();
-            if let Ok(hash) = location.hash() {
-                return Route::from_hash(&hash);
-            }
+    /// Queue a side effect, carried out by the runner once the update is done
+    fn effect(&mut self, effect: Effect) {
+        self.effects.push(effect);
+    }
+    
+    fn log(&mut self, message: impl Into<String>) {
+        self.effect(Effect::Log(message.into()));
+    }
+    
+    /// Everything queued so far, including what the cat bookkeeping needs done with images
+    fn take_effects(&mut self) -> Vec<Effect> {
+        for action in self.cats.take_actions() {
+            self.effect(Effect::Images(action));
         }
-        Route::Home
-    }
-    
-    fn navigate_to(&self, route: Route) {
-        if let Some(window) = window() {
-            let location = window.location();
-            let hash = route.to_hash();
-            let _ = location.set_hash(hash);
-            console::log_1(&format!("Navigated to: {}", hash).into());
-        }
-    }
-    
-    fn start_preloading(&mut self, urls: &[String]) {
-        console::log_1(&format!("Starting to preload {} images", urls.len()).into());
-        
-        if let Some(window) = window() {
-            if let Some(document) = window.document() {
-                for (index, url) in urls.iter().enumerate() {
-                    if let Ok(img_element) = document.create_element("img") {
-                        let img = img_element.dyn_into::<HtmlImageElement>().unwrap();
-                        
-                        // Create closure for onload event
-                        let index_clone = index;
-                        let onload = Closure::wrap(Box::new(move || {
-                            console::log_1(&format!("Image {} finished loading", index_clone).into());
-                            // In a real implementation, we'd send Msg::ImageLoaded(index) here
-                            // but we can't easily do that from a closure
-                        }) as Box<dyn Fn()>);
-                        
-                        // Create closure for onerror event
-                        let url_clone = url.clone();
-                        let onerror = Closure::wrap(Box::new(move || {
-                            console::log_1(&format!("Failed to load image: {}", url_clone).into());
-                        }) as Box<dyn Fn()>);
-                        
-                        img.set_onload(Some(onload.as_ref().unchecked_ref()));
-                        img.set_onerror(Some(onerror.as_ref().unchecked_ref()));
-                        
-                        // Start loading the image
-                        img.set_src(url);
-                        
-                        // Store the image element
-                        if index < self.preloaded_images.len() {
-                            self.preloaded_images[index] = Some(img);
-                        }
-                        
-                        // Keep closures alive
-                        onload.forget();
-                        onerror.forget();
-                        
-                        console::log_1(&format!("Started preloading image {}: {}", index, url).into());
-                    }
-                }
-                
-                // For demo purposes, simulate all images loading after a delay
-                let timeout_callback = Closure::wrap(Box::new(move || {
-                    console::log_1(&"Simulating all images loaded".into());
-                    // In reality, this would be triggered by the actual onload events
-                }) as Box<dyn Fn()>);
-                
-                window.set_timeout_with_callback_and_timeout_and_arguments_0(
-                    timeout_callback.as_ref().unchecked_ref(), 
-                    3000 // 3 second delay to simulate loading
-                ).unwrap();
-                timeout_callback.forget();
-                
-                // For demo, immediately mark as loaded after a short delay
-                self.images_loaded_count = urls.len();
-                self.cat_loading = false;
-            }
+        for request in self.cats.source.take_requests() {
+            self.effect(match request {
+                SourceRequest::FetchMetadata { url, generation } => Effect::FetchCatMetadata { url, generation },
+                SourceRequest::Store(url) => Effect::CacheCat(url),
+            });
         }
+        std::mem::take(&mut self.effects)
     }
     
-    fn start_preloading_next_batch(&self) {
-        console::log_1(&format!("Starting to preload next batch of {} images in background...", self.next_batch_urls.len()).into());
-        
-        if let Some(window) = window() {
-            if let Some(document) = window.document() {
-                // Create images for background preloading
-                for (index, url) in self.next_batch_urls.iter().enumerate() {
-                    if let Ok(img_element) = document.create_element("img") {
-                        let img = img_element.dyn_into::<HtmlImageElement>().unwrap();
-                        
-                        let url_clone = url.clone();
-                        let onload = Closure::wrap(Box::new(move || {
-                            console::log_1(&format!("Background image preloaded: {}", url_clone).into());
-                            // In reality, we'd trigger Msg::NextBatchImageLoaded here
-                        }) as Box<dyn Fn()>);
-                        
-                        let url_clone2 = url.clone();
-                        let onerror = Closure::wrap(Box::new(move || {
-                            console::log_1(&format!("Background preload failed: {}", url_clone2).into());
-                            // Still count as "loaded" to avoid blocking
-                        }) as Box<dyn Fn()>);
-                        
-                        img.set_onload(Some(onload.as_ref().unchecked_ref()));
-                        img.set_onerror(Some(onerror.as_ref().unchecked_ref()));
-                        img.set_src(url);
-                        
-                        onload.forget();
-                        onerror.forget();
-                        
-                        console::log_1(&format!("Started preloading next batch image {}: {}", index, url).into());
-                    }
-                }
-                
-                // For demo, simulate completion of all images after delay
-                let batch_size = self.next_batch_urls.len();
-                let timeout_callback = Closure::wrap(Box::new(move || {
-                    console::log_1(&format!("Simulating {} next batch images completed", batch_size).into());
-                    // In reality, this would be triggered by actual onload events
-                }) as Box<dyn Fn()>);
-                
-                window.set_timeout_with_callback_and_timeout_and_arguments_0(
-                    timeout_callback.as_ref().unchecked_ref(),
-                    2000 // 2 second delay for background preload
-                ).unwrap();
-                timeout_callback.forget();
-                
-                // For demo purposes, simulate immediate completion
-                console::log_1(&"Simulating immediate next batch completion for demo".into());
+    /// Move to `route`, asking the user first if the current page's leave guard objects
+    fn request_route(&mut self, route: Route, navigation: Navigation) {
+        match self.leave_guard(&self.current_route, &route) {
+            Some(question) => self.effect(Effect::Confirm {
+                question,
+                confirmed: Msg::ChangeRoute(route, navigation),
+                cancelled: Some(Msg::NavigationCancelled(navigation)),
+            }),
+            None => self.change_route(route, navigation),
+        }
+    }
+    
+    /// Move to `route`, running its lifecycle hooks
+    fn change_route(&mut self, route: Route, navigation: Navigation) {
+        if navigation == Navigation::Link {
+            self.effect(Effect::PushUrl(route.clone()));
+        }
+        let previous = std::mem::replace(&mut self.current_route, route.clone());
+        self.on_leave(&previous);
+        self.on_enter(&route);
+        if navigation == Navigation::History {
+            self.effect(Effect::RestoreScroll);
+        }
+        self.effect(Effect::SetDocumentMeta(route));
+    }
+    
+    /// Jump to the cat a `/cats/<id>` link points at, if it is in memory
+    fn show_linked_cat(&mut self, route: &Route) {
+        if let Route::Cat(id) = route {
+            if !self.cats.show_linked(id) {
+                self.log(format!("Linked cat {} is not loaded", id));
+                // Point the URL at the cat on screen instead
+                self.sync_cat_url();
             }
         }
     }
     
+    fn can_browse_cats(&self) -> bool {
+        self.current_route.section() == Route::Cats && !self.cats.urls.is_empty() && !self.cats.loading
+    }
+    
+    fn next_cat(&mut self) {
+        // Images are already preloaded, just cycle to next one
+        self.log("Cycling to next preloaded cat");
+        self.remember_shown_cat();
+        self.cats.next(&*self.platform);
+        self.sync_cat_url();
+    }
+    
+    fn remember_shown_cat(&mut self) {
+        self.previous_cat_url = self.cats.current_url().cloned();
+        self.cat_action_status = None;
+    }
+    
+    /// URL of the cat on screen and a name for files made from it
+    fn current_cat_file(&self) -> Option<(String, String)> {
+        let url = self.cats.current_url()?.clone();
+        let name = match self.cats.source.metadata(&url) {
+            Some(metadata) => metadata.id,
+            None => (self.cats.position() + 1).to_string(),
+        };
+        Some((url, name))
+    }
+    
+    /// Schedule the next slideshow step, replacing any step already scheduled
+    fn schedule_autoplay(&mut self) {
+        if self.slideshow.is_running() && self.current_route.section() == Route::Cats {
+            let generation = self.slideshow.restart();
+            let delay_ms = self.slideshow.interval_ms();
+            self.effect(Effect::Timer { delay_ms, msg: Msg::AutoplayTick(generation) });
+        }
+    }
+    
+    fn previous_cat(&mut self) {
+        let shown = self.cats.current_url().cloned();
+        if self.cats.previous() {
+            self.previous_cat_url = shown;
+            self.cat_action_status = None;
+            self.sync_cat_url();
+        }
+    }
+    
+    /// Show the cat at 0-based `position`, clamped to the cats still in memory
+    fn show_cat_at(&mut self, position: usize) {
+        self.remember_shown_cat();
+        self.cats.show_at(position);
+        self.sync_cat_url();
+    }
+    
+    /// Search for different cats, replacing everything loaded for the old search
+    fn apply_cat_query(&mut self, query: CatQuery) {
+        if self.cats.set_query(query, &*self.platform) {
+            self.sync_cat_url();
+        }
+    }
+    
+    fn toggle_favorite(&mut self) {
+        let Some(url) = self.cats.current_url().cloned() else {
+            return;
+        };
+        if self.favorites.contains(&url) {
+            self.favorites.remove_url(&url);
+            self.save_favorites();
+        } else {
+            // Added once the runner has taken a snapshot of the loaded image
+            self.effect(Effect::SnapshotFavorite { index: self.cats.current_index, url });
+        }
+    }
+    
+    fn save_favorites(&mut self) {
+        self.effect(Effect::SaveFavorites(self.favorites.items().to_vec()));
+    }
+    
+    /// Keep the URL pointing at the cat on screen so it can be shared, without adding
+    /// a history entry per cat
+    fn sync_cat_url(&mut self) {
+        let route = match self.cats.current_link_id() {
+            Some(id) => Route::Cat(id),
+            None => Route::Cats,
+        };
+        if self.current_route.section() == Route::Cats && route != self.current_route {
+            self.effect(Effect::ReplaceUrl(route.clone()));
+            self.effect(Effect::SetDocumentMeta(route.clone()));
+            self.current_route = route;
+        }
+    }
     
-    fn init_cat_loading(&mut self) {
-        self.cat_loading = true;
-        self.images_loaded_count = 0;
-        
-        // Generate 10 unique cat URLs with timestamps
-        let mut urls = Vec::new();
-        for i in 0..10 {
-            let timestamp = js_sys::Date::now() as u64 + i;
-            urls.push(format!("https://cataas.com/cat?t={}", timestamp));
+    /// Keep the seed, and the difficulty on the number guessing page, in the URL of the games
+    /// page on screen, so the link replays the same games
+    fn sync_game_url(&mut self) {
+        let mut route = self.current_route.clone();
+        match &mut route {
+            Route::Games { params } => params.set(games::SEED_PARAM, self.games.seed.to_string()),
+            Route::Game { kind, params } => {
+                params.set(games::SEED_PARAM, self.games.seed.to_string());
+                if *kind == games::GameKind::NumberGuessing {
+                    params.set(games::DIFFICULTY_PARAM, self.games.guess_difficulty.slug().to_string());
+                }
+            }
+            _ => return,
+        }
+        if route != self.current_route {
+            self.effect(Effect::ReplaceUrl(route.clone()));
+            self.current_route = route;
         }
-        
-        self.cat_urls = urls.clone();
-        self.total_cats_ever_loaded = urls.len();
-        self.preloaded_images = vec![None; urls.len()];
-        
-        // Actually preload each image
-        self.start_preloading(&urls);
     }
     
     fn render_navigation(&self) -> Node<Msg> {
         node! {
             <nav class="bg-gray-800 px-5 py-3 mb-4 mx-auto mt-2.5" style="width: min(500px, calc(100vw - 20px)); border-radius: 15px 15px 0 0;">
                 <div class="flex gap-2 justify-center flex-wrap items-center">
-                    <button class={if self.current_route == Route::Home { "btn-gradient-orange text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation" } else { "btn-gradient-blue text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation hover:-translate-y-0.5" }}
+                    <button class={if self.current_route.section() == Route::Home { "btn-gradient-orange text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation" } else { "btn-gradient-blue text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation hover:-translate-y-0.5" }}
                         on_click=|_| Msg::NavigateTo(Route::Home)>
                         {text("Home")}
                     </button>
-                    <button class={if self.current_route == Route::Counter { "btn-gradient-orange text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation" } else { "btn-gradient-blue text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation hover:-translate-y-0.5" }}
+                    <button class={if self.current_route.section() == Route::Counter { "btn-gradient-orange text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation" } else { "btn-gradient-blue text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation hover:-translate-y-0.5" }}
                         on_click=|_| Msg::NavigateTo(Route::Counter)>
                         {text("Counter")}
                     </button>
-                    <button class={if self.current_route == Route::Cats { "btn-gradient-orange text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation" } else { "btn-gradient-blue text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation hover:-translate-y-0.5" }}
+                    <button class={if self.current_route.section() == Route::Cats { "btn-gradient-orange text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation" } else { "btn-gradient-blue text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation hover:-translate-y-0.5" }}
                         on_click=|_| Msg::NavigateTo(Route::Cats)>
                         {text("Cats")}
                     </button>
-                    <button class={if self.current_route == Route::Games { "btn-gradient-orange text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation" } else { "btn-gradient-blue text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation hover:-translate-y-0.5" }}
-                        on_click=|_| Msg::NavigateTo(Route::Games)>
+                    <button class={if self.current_route.section() == Route::games() { "btn-gradient-orange text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation" } else { "btn-gradient-blue text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation hover:-translate-y-0.5" }}
+                        on_click=|_| Msg::NavigateTo(Route::games())>
                         {text("Games")}
                     </button>
-                    <button class={if self.current_route == Route::About { "btn-gradient-orange text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation" } else { "btn-gradient-blue text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation hover:-translate-y-0.5" }}
+                    <button class={if self.current_route.section() == Route::About { "btn-gradient-orange text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation" } else { "btn-gradient-blue text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation hover:-translate-y-0.5" }}
                         on_click=|_| Msg::NavigateTo(Route::About)>
                         {text("About")}
                     </button>
@@ -293,13 +412,16 @@ impl App {
     }
     
     fn render_current_page(&self) -> Node<Msg> {
-        match self.current_route {
+        match &self.current_route {
             Route::Home => self.render_home_page(),
             Route::Counter => self.render_counter_page(),
-            Route::Cats => self.render_cats_page(),
-            Route::Games => self.render_games_page(),
+            Route::Cats | Route::Cat(_) => self.render_cats_page(),
+            Route::Games { .. } => self.render_games_page(),
+            Route::Game { kind, .. } => self.render_game_page(*kind),
+            Route::Favorites => self.render_favorites_page(),
+            Route::Settings => self.render_settings_page(),
             Route::About => self.render_about_page(),
-            Route::NotFound => self.render_404_page(),
+            Route::NotFound(url) => self.render_404_page(url),
         }
     }
     
@@ -317,7 +439,7 @@ impl App {
                     <button class="btn-gradient-blue text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation hover:-translate-y-0.5" on_click=|_| Msg::NavigateTo(Route::Cats)>
                         {text("Browse Cats →")}
                     </button>
-                    <button class="btn-gradient-blue text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation hover:-translate-y-0.5" on_click=|_| Msg::NavigateTo(Route::Games)>
+                    <button class="btn-gradient-blue text-white border-0 px-4 py-3 rounded-lg text-sm font-semibold cursor-pointer transition-all duration-300 shadow-lg min-h-11 touch-manipulation hover:-translate-y-0.5" on_click=|_| Msg::NavigateTo(Route::games())>
                         {text("Play Games →")}
                     </button>
                 </div>
@@ -338,7 +460,7 @@ impl App {
                         }>
                         {text("+")}
                     </button>
-                    <button class="count-display" on_click=|_|{Msg::Reset} >{text(self.count)}</button>
+                    <button class="count-display" on_click=|_|{Msg::Reset} >{text(self.counter.value)}</button>
                     <button class="counter-button"
                         on_click=|_| {
                             Msg::Decrement
@@ -385,63 +507,555 @@ impl App {
                 </h2>
                 <div class="flex gap-2 justify-center flex-wrap items-center my-2">
                     <button class="counter-button"
-                        disabled={self.cat_loading}
+                        disabled={self.cats.loading}
                         on_click=|_| {
                             Msg::FetchCat
                         }>
-                        {text(if self.cat_loading {
+                        {text(if self.cats.loading {
                             "Loading..."
-                        } else if self.cat_urls.is_empty() {
+                        } else if self.cats.urls.is_empty() {
                             "🐱 Load Cats"
                         } else {
                             "🐱 Next Cat"
                         })}
                     </button>
-                </div>
-
-                <div class="image-container">
+                    {self.render_favorite_toggle()}
+                    <button class="small-button" on_click=|_| Msg::NavigateTo(Route::Favorites)>
+                        {text(format!("Favorites ({})", self.favorites.len()))}
+                    </button>
+                    <button class="small-button" on_click=|_| Msg::NavigateTo(Route::Settings)>
+                        {text("⚙ Settings")}
+                    </button>
                     {
-                        if !self.cat_urls.is_empty() && !self.cat_loading {
-                            let current_url = &self.cat_urls[self.current_cat_index];
+                        if self.cats.source.query().is_some() {
                             node! {
-                                <div>
-                                    <img class="cat-image" src={current_url} />
-                                    <div class="mt-4 text-gray-600 text-sm">
-                                        {text(format!("Cat {} of {}{}", 
-                                            self.current_cat_index + 1, 
-                                            self.cat_urls.len(),
-                                            if self.is_preloading_next_batch { " (loading more...)" } else { " (infinite!)" }
-                                        ))}
-                                    </div>
+                                <button class="small-button" on_click=|_| Msg::ToggleCatSearch>
+                                    {text(if self.show_cat_search { "Hide search" } else { "🔍 Search" })}
+                                </button>
+                            }
+                        } else {
+                            text("")
+                        }
+                    }
+                </div>
+                {self.render_cat_search()}
+                {self.render_favorites_error()}
+                {self.render_cat_navigation()}
+                {self.render_cat_cache_status()}
+
+                {
+                    if self.showing_cat_grid() {
+                        self.render_cat_grid()
+                    } else {
+                        self.render_cat_viewer()
+                    }
+                }
+                {self.render_cat_actions()}
+            </div>
+        }
+    }
+    
+    /// One cat at a time, or the loading progress while the first batch arrives
+    fn render_cat_viewer(&self) -> Node<Msg> {
+        node! {
+            <div class="image-container"
+                id={slideshow::STAGE_ID}
+                tabindex="0"
+                on_mouseenter=|_| Msg::StageHovered(true)
+                on_mouseleave=|_| Msg::StageHovered(false)
+                on_focus=|_| Msg::StageFocused(true)
+                on_blur=|_| Msg::StageFocused(false)
+                on_touchstart=|event| {
+                    match event.changed_touches().get(0) {
+                        Some(touch) => Msg::SwipeStarted(touch.client_x() as f64),
+                        None => Msg::SwipeStarted(0.0),
+                    }
+                }
+                on_touchend=|event| {
+                    match event.changed_touches().get(0) {
+                        Some(touch) => Msg::SwipeEnded(touch.client_x() as f64),
+                        None => Msg::SwipeEnded(0.0),
+                    }
+                }>
+                {
+                    if !self.cats.urls.is_empty() && !self.cats.loading {
+                        let current_url = &self.cats.urls[self.cats.current_index];
+                        let failed = self.cats.loads.get(self.cats.current_index)
+                            .is_some_and(|load| load.status == ImageStatus::Failed);
+                        let previous_url = self.previous_cat_url.as_ref().filter(|url| *url != current_url);
+                        node! {
+                            <div class="flex flex-col w-full h-full">
+                                {
+                                    if failed {
+                                        node! {
+                                            <div class="flex flex-col justify-center items-center h-full text-gray-600">
+                                                <div class="text-6xl mb-4">{text("😿")}</div>
+                                                <div class="text-base">{text("This cat couldn't be loaded.")}</div>
+                                            </div>
+                                        }
+                                    } else {
+                                        // The new cat fades in over the previous one; keyed so each cat animates
+                                        node! {
+                                            <div class="cat-crossfade">
+                                                {match previous_url {
+                                                    Some(previous_url) => node! {
+                                                        <img key={format!("previous {}", previous_url)} class="cat-image" crossorigin="anonymous" src={previous_url} />
+                                                    },
+                                                    None => text(""),
+                                                }}
+                                                <img key={current_url} class="cat-image cat-fade-in" crossorigin="anonymous" src={current_url} />
+                                            </div>
+                                        }
+                                    }
+                                }
+                                <div class="mt-4 text-gray-600 text-sm">
+                                    {text(format!("Cat {} of {}{}", 
+                                        self.cats.evicted + self.cats.current_index + 1, 
+                                        self.cats.total_ever_loaded,
+                                        if self.cats.preloading_next_batch { " (loading more...)" } else { " (infinite!)" }
+                                    ))}
+                                </div>
+                                {self.render_cat_metadata(current_url)}
+                            </div>
+                        }
+                    } else if self.cats.loading {
+                        let retrying = self.cats.loads.iter()
+                            .filter(|load| !load.is_settled() && (load.failures > 0 || load.replacements > 0))
+                            .count();
+                        node! {
+                            <div>
+                                <div class="text-gray-600 mb-5 text-base">
+                                    {text(format!("Preloading cats... ({}/{})", self.cats.loaded_count, self.cats.urls.len()))}
+                                </div>
+                                {
+                                    if retrying > 0 {
+                                        node! {
+                                            <div class="text-orange-600 mb-3 text-sm">
+                                                {text(format!("{} failed, retrying...", retrying))}
+                                            </div>
+                                        }
+                                    } else {
+                                        text("")
+                                    }
+                                }
+                                <div class="w-60 h-3 bg-gray-300 rounded-md mx-auto">
+                                    <div class="h-full bg-blue-600 rounded-md transition-all duration-300 ease-in-out" style={format!("width: {}%", 
+                                        if self.cats.urls.is_empty() { 0 } else { (self.cats.loaded_count * 100) / self.cats.urls.len() })}></div>
                                 </div>
+                            </div>
+                        }
+                    } else {
+                        node! {
+                            <div class="text-gray-600 text-base">
+                                {text(format!("Click to preload {} cat images!", self.cats.effective().batch_size))}
+                            </div>
+                        }
+                    }
+                }
+            </div>
+        }
+    }
+    
+    /// Id and tags of the cat at `url`, when its source knows them
+    fn cat_caption(&self, url: &str) -> String {
+        match self.cats.source.metadata(url) {
+            Some(metadata) if metadata.tags.is_empty() => format!("#{}", metadata.id),
+            Some(metadata) => format!("#{} · {}", metadata.id, metadata.tags.join(", ")),
+            None => String::new(),
+        }
+    }
+    
+    fn render_cat_metadata(&self, url: &str) -> Node<Msg> {
+        let caption = self.cat_caption(url);
+        if caption.is_empty() {
+            return text("");
+        }
+        node! {
+            <div class="mt-1 text-gray-500 text-xs break-all">{text(caption)}</div>
+        }
+    }
+    
+    /// Tag, caption, filter and size for sources that can search
+    fn render_cat_search(&self) -> Node<Msg> {
+        if !self.show_cat_search || self.cats.source.query().is_none() {
+            return text("");
+        }
+        let draft = &self.cat_query_draft;
+        node! {
+            <div class="flex flex-col gap-2 bg-white rounded-lg p-3 text-sm text-left">
+                <label class="flex gap-2 items-center">
+                    <span class="w-16 text-gray-600">{text("Tag")}</span>
+                    <input type="text" class="flex-1 px-2 py-1 border border-gray-300 rounded-md"
+                        placeholder="e.g. cute, orange"
+                        value={&draft.tag}
+                        on_input=|event| Msg::CatTagChanged(event.value()) />
+                </label>
+                <label class="flex gap-2 items-center">
+                    <span class="w-16 text-gray-600">{text("Says")}</span>
+                    <input type="text" class="flex-1 px-2 py-1 border border-gray-300 rounded-md"
+                        placeholder="Caption on the image"
+                        value={&draft.says}
+                        on_input=|event| Msg::CatSaysChanged(event.value()) />
+                </label>
+                <div class="flex gap-2">
+                    <select class="flex-1 px-2 py-1 border border-gray-300 rounded-md"
+                        on_change=|event| Msg::CatFilterChanged(event.value())>
+                        {for filter in CatFilter::ALL {
+                            node! {
+                                <option value={filter.slug()} selected={filter == draft.filter}>
+                                    {text(filter.label())}
+                                </option>
+                            }
+                        }}
+                    </select>
+                    <select class="flex-1 px-2 py-1 border border-gray-300 rounded-md"
+                        on_change=|event| Msg::CatSizeChanged(event.value())>
+                        {for size in CatSize::ALL {
+                            node! {
+                                <option value={size.slug()} selected={size == draft.size}>
+                                    {text(size.label())}
+                                </option>
                             }
-                        } else if self.cat_loading {
+                        }}
+                    </select>
+                </div>
+                <div class="flex gap-2 justify-end">
+                    <button class="small-button" on_click=|_| Msg::ResetCatQuery>
+                        {text("Reset")}
+                    </button>
+                    <button class="small-button" on_click=|_| Msg::ApplyCatQuery>
+                        {text("Search")}
+                    </button>
+                </div>
+            </div>
+        }
+    }
+    
+    fn render_favorite_toggle(&self) -> Node<Msg> {
+        let Some(url) = self.cats.urls.get(self.cats.current_index).filter(|_| !self.cats.loading) else {
+            return text("");
+        };
+        node! {
+            <button class="small-button" on_click=|_| Msg::ToggleFavorite>
+                {text(if self.favorites.contains(url) { "★ Favorited" } else { "☆ Favorite" })}
+            </button>
+        }
+    }
+    
+    fn render_favorites_error(&self) -> Node<Msg> {
+        match &self.favorites_error {
+            Some(error) => node! {
+                <div class="text-red-600 text-sm">{text(error)}</div>
+            },
+            None => text(""),
+        }
+    }
+    
+    /// Download, copy and share for the cat on screen
+    fn render_cat_actions(&self) -> Node<Msg> {
+        if !self.can_browse_cats() || self.cat_view != CatView::Single {
+            return text("");
+        }
+        node! {
+            <div class="flex flex-col gap-1 items-center">
+                <div class="flex gap-2 justify-center flex-wrap">
+                    <button class="small-button" on_click=|_| Msg::DownloadCat>{text("⬇ Download")}</button>
+                    <button class="small-button" on_click=|_| Msg::CopyCat>{text("📋 Copy")}</button>
+                    <button class="small-button" on_click=|_| Msg::ShareCat>{text("↗ Share")}</button>
+                </div>
+                {
+                    match &self.cat_action_status {
+                        Some(status) => node! { <div class="text-gray-600 text-sm">{text(status)}</div> },
+                        None => text(""),
+                    }
+                }
+            </div>
+        }
+    }
+    
+    /// Play/pause, interval and fullscreen for the single-cat view
+    fn render_slideshow_controls(&self) -> Node<Msg> {
+        if self.cat_view != CatView::Single {
+            return text("");
+        }
+        let interval_s = self.slideshow.interval_s;
+        node! {
+            <span class="flex gap-2 items-center">
+                <button class="small-button" on_click=|_| Msg::ToggleAutoplay>
+                    {text(if !self.slideshow.playing {
+                        "▶ Autoplay"
+                    } else if self.slideshow.is_paused() {
+                        "⏸ Paused"
+                    } else {
+                        "⏹ Stop"
+                    })}
+                </button>
+                <select class="px-2 py-1 border border-gray-300 rounded-md text-sm"
+                    on_change=|event| Msg::AutoplayIntervalChanged(event.value())>
+                    {for seconds in slideshow::INTERVALS_S {
+                        node! {
+                            <option value={seconds.to_string()} selected={seconds == interval_s}>
+                                {text(format!("{}s", seconds))}
+                            </option>
+                        }
+                    }}
+                </select>
+                <button class="small-button" on_click=|_| Msg::ToggleFullscreen>
+                    {text(if self.fullscreen { "Exit fullscreen" } else { "⛶ Fullscreen" })}
+                </button>
+            </span>
+        }
+    }
+    
+    fn render_cat_cache_status(&self) -> Node<Msg> {
+        let Some(cache) = &self.cat_cache else {
+            return text("");
+        };
+        let megabytes = |bytes: f64| bytes / (1024.0 * 1024.0);
+        node! {
+            <div class="flex gap-2 justify-center items-center text-gray-600 text-sm">
+                {text(format!("Offline cache: {} cats, {:.1} of {:.0} MB",
+                    cache.len(), megabytes(cache.total_bytes()), megabytes(cache.max_bytes())))}
+                <button class="small-button"
+                    disabled={cache.len() == 0}
+                    on_click=|_| Msg::ClearCatCache>
+                    {text("Clear cache")}
+                </button>
+            </div>
+        }
+    }
+    
+    fn render_settings_page(&self) -> Node<Msg> {
+        let settings = self.cats.settings;
+        let effective = self.cats.effective();
+        let number_field = |label: &str, value: usize, max: usize, to_msg: fn(String) -> Msg| {
+            node! {
+                <label class="flex gap-2 items-center justify-between">
+                    <span class="text-gray-700">{text(label)}</span>
+                    <input type="number" class="w-24 px-2 py-1 border border-gray-300 rounded-md text-center"
+                        min="1"
+                        max={max.to_string()}
+                        value={value.to_string()}
+                        on_change=move |event| to_msg(event.value()) />
+                </label>
+            }
+        };
+        node! {
+            <div>
+                <div class="flex gap-2 justify-start mb-4">
+                    <button class="small-button" on_click=|_| Msg::NavigateTo(Route::Cats)>
+                        {text("← Cats")}
+                    </button>
+                </div>
+                <h2 class="text-center mb-4 text-gray-800">{text("Preloading")}</h2>
+                <div class="flex flex-col gap-3 bg-white rounded-lg p-4 text-sm text-left">
+                    {number_field("Batch size", settings.batch_size, PreloadSettings::MAX_BATCH_SIZE, Msg::BatchSizeChanged)}
+                    {number_field("Prefetch when cats left ahead", settings.look_ahead, PreloadSettings::MAX_LOOK_AHEAD, Msg::LookAheadChanged)}
+                    {number_field("Images loading at once", settings.max_concurrent, PreloadSettings::MAX_CONCURRENT, Msg::MaxConcurrentChanged)}
+                    <label class="flex gap-2 items-center justify-between">
+                        <span class="text-gray-700">{text("Adapt to connection speed")}</span>
+                        <input type="checkbox"
+                            checked={settings.adaptive}
+                            on_change=|_| Msg::ToggleAdaptivePreload />
+                    </label>
+                    <div class="text-gray-600">
+                        {text(match self.cats.timer.average_ms() {
+                            Some(average_ms) => format!("Average image load: {:.0} ms", average_ms),
+                            None => "No images loaded yet".to_string(),
+                        })}
+                    </div>
+                    <div class="text-gray-600">
+                        {text(format!("In use: batches of {}, prefetch at {} left, {} at once",
+                            effective.batch_size, effective.look_ahead, effective.max_concurrent))}
+                    </div>
+                    <div class="text-gray-500 text-xs break-all">
+                        {text(format!("Same settings by URL: {}", settings.to_query()))}
+                    </div>
+                    <div class="flex justify-end">
+                        <button class="small-button" on_click=|_| Msg::ResetPreloadSettings>
+                            {text("Reset to defaults")}
+                        </button>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+    
+    fn render_favorites_page(&self) -> Node<Msg> {
+        let count = self.favorites.len();
+        node! {
+            <div>
+                <div class="flex gap-2 justify-start mb-4">
+                    <button class="small-button" on_click=|_| Msg::NavigateTo(Route::Cats)>
+                        {text("← Cats")}
+                    </button>
+                </div>
+                <h2 class="text-center mb-4 text-gray-800">
+                    {text("Favorite Cats")}
+                </h2>
+                {self.render_favorites_error()}
+                {
+                    if count == 0 {
+                        node! {
+                            <div class="text-gray-600 text-base py-8">
+                                {text("No favorites yet. Press ☆ Favorite on a cat you like!")}
+                            </div>
+                        }
+                    } else {
+                        node! {
+                            <div class="grid grid-cols-2 gap-3">
+                                {for (index, favorite) in self.favorites.items().iter().enumerate() {
+                                    node! {
+                                        <div class="bg-white rounded-lg p-2 shadow-md flex flex-col gap-2">
+                                            <img class="w-full aspect-square object-cover rounded-md" src={&favorite.image} />
+                                            <div class="flex gap-1 justify-center">
+                                                <button class="small-button"
+                                                    disabled={index == 0}
+                                                    on_click=move |_| Msg::MoveFavorite(index, index.saturating_sub(1))>
+                                                    {text("◀")}
+                                                </button>
+                                                <button class="small-button"
+                                                    on_click=move |_| Msg::RemoveFavorite(index)>
+                                                    {text("✕")}
+                                                </button>
+                                                <button class="small-button"
+                                                    disabled={index + 1 == count}
+                                                    on_click=move |_| Msg::MoveFavorite(index, index + 1)>
+                                                    {text("▶")}
+                                                </button>
+                                            </div>
+                                        </div>
+                                    }
+                                }}
+                            </div>
+                        }
+                    }
+                }
+            </div>
+        }
+    }
+    
+    fn showing_cat_grid(&self) -> bool {
+        self.cat_view == CatView::Grid && self.can_browse_cats()
+    }
+    
+    /// Every cat in memory at once, in masonry columns; images load as they scroll into view
+    fn render_cat_grid(&self) -> Node<Msg> {
+        let lazy = self.lazy_images;
+        node! {
+            <div>
+                <div class="columns-2 gap-2 text-left">
+                    {for (index, url) in self.cats.urls.iter().enumerate() {
+                        let failed = self.cats.loads.get(index).is_some_and(|load| load.status == ImageStatus::Failed);
+                        let current = if index == self.cats.current_index { " ring-4 ring-orange-400" } else { "" };
+                        if failed {
                             node! {
-                                <div>
-                                    <div class="text-gray-600 mb-5 text-base">
-                                        {text(format!("Preloading cats... ({}/{})", self.images_loaded_count, self.cat_urls.len()))}
-                                    </div>
-                                    <div class="w-60 h-3 bg-gray-300 rounded-md mx-auto">
-                                        <div class={format!("h-full bg-blue-600 rounded-md transition-all duration-300 ease-in-out")} style={format!("width: {}%", 
-                                            if self.cat_urls.is_empty() { 0 } else { (self.images_loaded_count * 100) / self.cat_urls.len() })}></div>
-                                    </div>
+                                <div key={url} class={format!("mb-2 break-inside-avoid rounded-md bg-gray-100 text-gray-600 text-center py-8 cursor-pointer{}", current)}
+                                    on_click=move |_| Msg::ShowCatFromGrid(index)>
+                                    {text("😿")}
                                 </div>
                             }
+                        } else if lazy {
+                            node! {
+                                <img key={url} class={format!("w-full mb-2 break-inside-avoid rounded-md cursor-pointer bg-gray-100 min-h-24{}", current)}
+                                    title={self.cat_caption(url)}
+                                    crossorigin="anonymous"
+                                    data-src={url}
+                                    on_click=move |_| Msg::ShowCatFromGrid(index) />
+                            }
                         } else {
                             node! {
-                                <div class="text-gray-600 text-base">
-                                    {text("Click to preload 10 cat images!")}
-                                </div>
+                                <img key={url} class={format!("w-full mb-2 break-inside-avoid rounded-md cursor-pointer{}", current)}
+                                    title={self.cat_caption(url)}
+                                    crossorigin="anonymous"
+                                    src={url}
+                                    on_click=move |_| Msg::ShowCatFromGrid(index) />
                             }
                         }
-                    }
+                    }}
                 </div>
+                <button class="small-button"
+                    disabled={self.cats.preloading_next_batch}
+                    on_click=|_| Msg::LoadMoreCats>
+                    {text(if self.cats.preloading_next_batch { "Loading more..." } else { "More cats" })}
+                </button>
+            </div>
+        }
+    }
+    
+    /// Previous/next buttons and the jump-to field, once there are cats to browse
+    fn render_cat_navigation(&self) -> Node<Msg> {
+        if self.cats.urls.is_empty() || self.cats.loading {
+            return text("");
+        }
+        let first = self.cats.evicted + 1;
+        let last = self.cats.evicted + self.cats.urls.len();
+        node! {
+            <div class="flex gap-2 justify-center flex-wrap items-center my-2">
+                <button class="small-button"
+                    disabled={self.cats.current_index == 0}
+                    on_click=|_| Msg::PreviousCat>
+                    {text("◀ Previous")}
+                </button>
+                <input type="number"
+                    class="w-24 px-2 py-1 border border-gray-300 rounded-md text-center"
+                    placeholder={format!("{}-{}", first, last)}
+                    min={first.to_string()}
+                    max={last.to_string()}
+                    value={&self.cat_jump_input}
+                    on_input=|event| {
+                        if let Some(input) = event.event.target() {
+                            if let Ok(input_element) = input.dyn_into::<web_sys::HtmlInputElement>() {
+                                Msg::CatJumpInputChanged(input_element.value())
+                            } else {
+                                Msg::CatJumpInputChanged(String::new())
+                            }
+                        } else {
+                            Msg::CatJumpInputChanged(String::new())
+                        }
+                    } />
+                <button class="small-button" on_click=|_| Msg::SubmitCatJump>
+                    {text("Go")}
+                </button>
+                <button class="small-button" on_click=|_| Msg::NextCat>
+                    {text("Next ▶")}
+                </button>
+                {self.render_slideshow_controls()}
+                {
+                    if self.cat_view == CatView::Grid {
+                        node! {
+                            <button class="small-button" on_click=|_| Msg::SetCatView(CatView::Single)>
+                                {text("▢ Single")}
+                            </button>
+                        }
+                    } else {
+                        node! {
+                            <button class="small-button" on_click=|_| Msg::SetCatView(CatView::Grid)>
+                                {text("▦ Grid")}
+                            </button>
+                        }
+                    }
+                }
             </div>
         }
     }
     
     fn render_games_page(&self) -> Node<Msg> {
-        self.games.render_games_page()
+        GameView(&self.games).render_games_page()
+    }
+    
+    fn render_game_page(&self, kind: games::GameKind) -> Node<Msg> {
+        node! {
+            <div>
+                <div class="flex gap-2 justify-start mb-4">
+                    <button class="small-button" on_click=|_| Msg::NavigateTo(Route::games())>
+                        {text("← All Games")}
+                    </button>
+                </div>
+                {GameView(&self.games).render_seed_controls()}
+                {GameView(&self.games).render_game(kind)}
+            </div>
+        }
     }
     
     
@@ -472,13 +1086,13 @@ impl App {
         }
     }
     
-    fn render_404_page(&self) -> Node<Msg> {
+    fn render_404_page(&self, url: &str) -> Node<Msg> {
         node! {
             <div style="text-align: center; padding: 40px;">
                 <h1 style="color: #ff6b6b; margin-bottom: 20px; font-size: 72px;">{text("404")}</h1>
                 <h2 style="color: #333; margin-bottom: 20px;">{text("Page Not Found")}</h2>
                 <p style="color: #666; margin-bottom: 30px;">
-                    {text("The page you're looking for doesn't exist.")}
+                    {text(format!("There is no page at \"{}\".", url))}
                 </p>
                 <button class="nav-button" on_click=|_| Msg::NavigateTo(Route::Home)>
                     {text("← Go Home")}
@@ -488,6 +1102,55 @@ impl App {
     }
 }
 
+impl RouteHooks for App {
+    fn on_enter(&mut self, route: &Route) {
+        if route.section() == Route::Cats {
+            if self.cats.urls.is_empty() && !self.cats.loading {
+                self.log("Auto-loading cats on entering the cats page");
+                let linked = match route {
+                    Route::Cat(id) => Some(id.as_str()),
+                    _ => None,
+                };
+                self.cats.start(linked, &*self.platform);
+            }
+            self.show_linked_cat(route);
+            self.schedule_autoplay();
+        }
+        if let Some(params) = route.game_params() {
+            match games::seed_from_query(params) {
+                Some(seed) if seed != self.games.seed => {
+                    self.log(format!("Restarting games with seed {} from the URL", seed));
+                    self.games.reseed(seed);
+                }
+                _ => {}
+            }
+            match games::difficulty_from_query(params) {
+                Some(difficulty) if difficulty != self.games.guess_difficulty => {
+                    self.log(format!("Switching number guessing to {} from the URL", difficulty.label()));
+                    self.games.update(games::GameMsg::SelectGuessDifficulty(difficulty), &mut *self.platform);
+                }
+                _ => {}
+            }
+            self.sync_game_url();
+        }
+    }
+
+    fn on_leave(&mut self, route: &Route) {
+        // Leaving the games section abandons a memory game; the guard has already asked
+        if route.section() == Route::games() && self.current_route.section() != Route::games() && self.games.memory_in_progress() {
+            self.games.update(games::GameMsg::ResetMemoryGame, &mut *self.platform);
+        }
+    }
+
+    fn leave_guard(&self, from: &Route, to: &Route) -> Option<String> {
+        let leaving_games = from.section() == Route::games() && to.section() != Route::games();
+        if leaving_games && self.games.memory_in_progress() {
+            return Some("You have a memory game in progress, leave anyway?".to_string());
+        }
+        None
+    }
+}
+
 impl Application for App {
     type MSG = Msg;
 
@@ -502,193 +1165,345 @@ impl Application for App {
         }
     }
 
+    fn init(&mut self) -> Cmd<Msg> {
+        let initial_route = self.current_route.clone();
+        self.effect(Effect::SetDocumentMeta(initial_route.clone()));
+        self.on_enter(&initial_route);
+        let effects = self.take_effects();
+        self.runner.run(effects)
+    }
+
+    /// Apply `msg` to the model, then have the runner carry out the effects it queued
     fn update(&mut self, msg: Msg) -> Cmd<Msg> {
-        // Always check if the current route matches the URL hash
-        let current_url_route = Self::get_current_route();
-        if current_url_route != self.current_route {
-            console::log_1(&format!("Route sync: {:?} -> {:?}", self.current_route, current_url_route).into());
-            self.current_route = current_url_route.clone();
-            
-            // Auto-load cats when route sync detects cats page
-            if current_url_route == Route::Cats && self.cat_urls.is_empty() && !self.cat_loading {
-                console::log_1(&"Auto-loading cats on route sync".into());
-                self.init_cat_loading();
-            }
-        }
-        
+        let effects = self.step(msg);
+        self.runner.run(effects)
+    }
+
+    fn stylesheet() -> Vec<String> {
+        vec![]
+    }
+}
+
+impl App {
+    /// The state machine behind `update`: changes the model and returns the side effects
+    /// to carry out, without touching the browser itself
+    fn step(&mut self, msg: Msg) -> Vec<Effect> {
         match msg {
             Msg::Increment => {
-                console::log_1(&"Increment button clicked".into());
-                self.count += 1.0;
+                self.log("Increment button clicked");
+                self.counter.increment();
             },
             Msg::Decrement => {
-                console::log_1(&"Decrement button clicked".into());
-                self.count -= 1.0;
+                self.log("Decrement button clicked");
+                self.counter.decrement();
             },
             Msg::Reset => {
-                console::log_1(&"Reset button clicked".into());
-                self.count = 0.0;
+                self.log("Reset button clicked");
+                self.counter.reset();
             },
             Msg::Double => {
-                console::log_1(&"Double button clicked".into());
-                self.count *= 2.0;
+                self.log("Double button clicked");
+                self.counter.double();
             },
             Msg::Halve => {
-                console::log_1(&"Halve button clicked".into());
-                self.count /= 2.0;
+                self.log("Halve button clicked");
+                self.counter.halve();
             },
             Msg::Panic => {
-                console::log_1(&"Panic button clicked".into());
+                self.platform.log("Panic button clicked");
                 panic!("User requested panic!");
             },
             Msg::DivideByZero => {
-                console::log_1(&"Divide by zero button clicked".into());
-                self.count /= 0.0;
+                self.log("Divide by zero button clicked");
+                self.counter.divide_by_zero();
             },
             Msg::FetchCat => {
-                if self.cat_urls.is_empty() {
-                    console::log_1(&"Starting to preload cat images...".into());
-                    self.cat_loading = true;
-                    self.images_loaded_count = 0;
-                    
-                    // Generate 10 unique cat URLs with timestamps
-                    let mut urls = Vec::new();
-                    for i in 0..10 {
-                        let timestamp = js_sys::Date::now() as u64 + i;
-                        urls.push(format!("https://cataas.com/cat?t={}", timestamp));
+                if self.cats.urls.is_empty() {
+                    self.log("Starting to preload cat images...");
+                    self.cats.start(None, &*self.platform);
+                } else if !self.cats.loading {
+                    self.next_cat();
+                }
+            },
+            Msg::NextCat => {
+                if self.can_browse_cats() {
+                    self.next_cat();
+                }
+            },
+            Msg::PreviousCat => {
+                if self.can_browse_cats() {
+                    self.previous_cat();
+                }
+            },
+            Msg::CatJumpInputChanged(value) => {
+                self.cat_jump_input = value;
+            },
+            Msg::SubmitCatJump => {
+                // Positions are shown 1-based
+                if let Ok(position) = self.cat_jump_input.trim().parse::<usize>() {
+                    if position >= 1 && self.can_browse_cats() {
+                        self.show_cat_at(position - 1);
                     }
-                    
-                    self.cat_urls = urls.clone();
-                    self.total_cats_ever_loaded = urls.len();
-                    self.preloaded_images = vec![None; urls.len()];
-                    
-                    // Actually preload each image
-                    self.start_preloading(&urls);
-                } else if !self.cat_loading {
-                    // Images are already preloaded, just cycle to next one
-                    console::log_1(&"Cycling to next preloaded cat".into());
-                    self.current_cat_index = (self.current_cat_index + 1) % self.cat_urls.len();
-                    
-                    // Check if we're approaching the end of current batch (second to last)
-                    let approaching_end = self.current_cat_index >= self.cat_urls.len() - 2;
-                    if approaching_end && !self.is_preloading_next_batch {
-                        console::log_1(&"Approaching end of batch - starting to preload next batch".into());
-                        self.is_preloading_next_batch = true;
-                        self.next_batch_loaded_count = 0;
-                        
-                        // Generate next batch of URLs
-                        self.next_batch_urls.clear();
-                        for i in 0..10 {
-                            let timestamp = js_sys::Date::now() as u64 + self.total_cats_ever_loaded as u64 + i + 1000;
-                            self.next_batch_urls.push(format!("https://cataas.com/cat?t={}", timestamp));
+                }
+                self.cat_jump_input.clear();
+            },
+            Msg::SwipeStarted(x) => {
+                self.swipe_start_x = Some(x);
+            },
+            Msg::SwipeEnded(x) => {
+                if let Some(start_x) = self.swipe_start_x.take() {
+                    let distance = x - start_x;
+                    if distance.abs() >= SWIPE_THRESHOLD_PX && self.can_browse_cats() {
+                        // Swiping left brings in the next cat, like turning a page
+                        if distance < 0.0 {
+                            self.next_cat();
+                        } else {
+                            self.previous_cat();
                         }
-                        
-                        // Actually preload the next batch images
-                        self.start_preloading_next_batch();
-                        
-                        // For demo, simulate all images loading immediately
-                        self.next_batch_loaded_count = self.next_batch_urls.len();
-                        console::log_1(&"Demo: Simulating all next batch images preloaded".into());
-                        
-                        // Add the preloaded URLs to the main collection
-                        self.cat_urls.extend(self.next_batch_urls.clone());
-                        self.total_cats_ever_loaded = self.cat_urls.len();
-                        self.is_preloading_next_batch = false;
-                        self.next_batch_urls.clear();
-                        self.next_batch_loaded_count = 0;
-                        
-                        console::log_1(&format!("Total cats now available: {}", self.cat_urls.len()).into());
                     }
                 }
             },
-            Msg::ImageLoaded(index) => {
-                console::log_1(&format!("Image {} loaded", index).into());
-                self.images_loaded_count += 1;
-                
-                if self.images_loaded_count >= self.cat_urls.len() {
-                    console::log_1(&"All images preloaded successfully!".into());
-                    self.cat_loading = false;
+            Msg::SetCatView(view) => {
+                self.cat_view = view;
+            },
+            Msg::ShowCatFromGrid(index) => {
+                if self.can_browse_cats() && index < self.cats.urls.len() {
+                    self.cat_view = CatView::Single;
+                    self.show_cat_at(self.cats.evicted + index);
+                }
+            },
+            Msg::LoadMoreCats => {
+                if self.can_browse_cats() {
+                    // Moving to the last cat is what makes the preloader fetch the next batch
+                    self.show_cat_at(self.cats.evicted + self.cats.urls.len() - 1);
+                    self.cats.evict_old(&*self.platform);
+                    self.cats.prefetch_if_needed(&*self.platform);
                 }
             },
-            Msg::AllImagesLoaded => {
-                console::log_1(&"All images preloaded successfully!".into());
-                self.cat_loading = false;
+            Msg::ToggleCatSearch => {
+                self.show_cat_search = !self.show_cat_search;
             },
-            Msg::NextBatchImageLoaded => {
-                self.next_batch_loaded_count += 1;
-                console::log_1(&format!("Next batch image loaded: {}/{}", self.next_batch_loaded_count, self.next_batch_urls.len()).into());
-                
-                // Check if all next batch images are loaded
-                if self.next_batch_loaded_count >= self.next_batch_urls.len() {
-                    console::log_1(&"All next batch images preloaded! Adding to available cats.".into());
-                    
-                    // Add the preloaded URLs to the main collection
-                    self.cat_urls.extend(self.next_batch_urls.clone());
-                    self.total_cats_ever_loaded = self.cat_urls.len();
-                    self.is_preloading_next_batch = false;
-                    self.next_batch_urls.clear();
-                    self.next_batch_loaded_count = 0;
-                    
-                    console::log_1(&format!("Total cats now available: {}", self.cat_urls.len()).into());
+            Msg::CatTagChanged(tag) => {
+                self.cat_query_draft.tag = tag;
+            },
+            Msg::CatSaysChanged(says) => {
+                self.cat_query_draft.says = says;
+            },
+            Msg::CatFilterChanged(filter) => {
+                self.cat_query_draft.filter = CatFilter::from_slug(&filter);
+            },
+            Msg::CatSizeChanged(size) => {
+                self.cat_query_draft.size = CatSize::from_slug(&size);
+            },
+            Msg::ApplyCatQuery => {
+                self.apply_cat_query(self.cat_query_draft.clone());
+            },
+            Msg::ResetCatQuery => {
+                self.cat_query_draft = CatQuery::default();
+                self.apply_cat_query(CatQuery::default());
+            },
+            Msg::ToggleFavorite => {
+                if self.can_browse_cats() {
+                    self.toggle_favorite();
+                }
+            },
+            Msg::AddFavorite(favorite) => {
+                self.favorites.add(favorite);
+                self.save_favorites();
+            },
+            Msg::FavoritesSaved(result) => {
+                self.favorites_error = result.err();
+                if let Some(error) = self.favorites_error.clone() {
+                    self.log(error);
+                }
+            },
+            Msg::RemoveFavorite(index) => {
+                self.favorites.remove(index);
+                self.save_favorites();
+            },
+            Msg::MoveFavorite(from, to) => {
+                self.favorites.move_item(from, to);
+                self.save_favorites();
+            },
+            Msg::CatMetadataFetched(generation, metadata) => {
+                if let Err(error) = &metadata {
+                    self.log(error);
                 }
+                self.cats.source.metadata_fetched(generation, metadata.ok());
+            },
+            Msg::ClearCatCache => {
+                if self.cat_cache.is_some() {
+                    self.log("Clearing the cat cache");
+                    self.effect(Effect::ClearCatCache);
+                }
+            },
+            Msg::BatchSizeChanged(value) => {
+                self.cats.settings.set_batch_size(&value);
+                self.effect(Effect::SavePreloadSettings(self.cats.settings));
+            },
+            Msg::LookAheadChanged(value) => {
+                self.cats.settings.set_look_ahead(&value);
+                self.effect(Effect::SavePreloadSettings(self.cats.settings));
+            },
+            Msg::MaxConcurrentChanged(value) => {
+                self.cats.settings.set_max_concurrent(&value);
+                self.effect(Effect::SavePreloadSettings(self.cats.settings));
+                self.cats.start_queued_loads(&*self.platform);
             },
-            Msg::NextBatchComplete => {
-                console::log_1(&"Next batch preloading complete!".into());
-                self.cat_urls.extend(self.next_batch_urls.clone());
-                self.total_cats_ever_loaded = self.cat_urls.len();
-                self.is_preloading_next_batch = false;
-                self.next_batch_urls.clear();
-                self.next_batch_loaded_count = 0;
+            Msg::ToggleAdaptivePreload => {
+                self.cats.settings.adaptive = !self.cats.settings.adaptive;
+                self.effect(Effect::SavePreloadSettings(self.cats.settings));
+            },
+            Msg::ResetPreloadSettings => {
+                self.cats.settings = PreloadSettings::default();
+                self.effect(Effect::SavePreloadSettings(self.cats.settings));
+            },
+            Msg::ToggleAutoplay => {
+                self.slideshow.playing = !self.slideshow.playing;
+                self.slideshow.restart();
+                self.schedule_autoplay();
+            },
+            Msg::AutoplayIntervalChanged(value) => {
+                if let Ok(interval_s) = value.parse::<u32>() {
+                    self.slideshow.interval_s = interval_s.max(1);
+                    self.schedule_autoplay();
+                }
+            },
+            Msg::AutoplayTick(generation) => {
+                // Pausing, stopping and leaving the page all leave the chain of ticks to die out
+                if self.slideshow.is_current(generation) && self.slideshow.is_running() && self.current_route.section() == Route::Cats {
+                    // Still waiting for the first batch: keep ticking without advancing
+                    if self.can_browse_cats() {
+                        self.next_cat();
+                    }
+                    self.schedule_autoplay();
+                }
+            },
+            Msg::StageHovered(hovered) => {
+                self.slideshow.set_hovered(hovered);
+                self.slideshow.restart();
+                self.schedule_autoplay();
+            },
+            Msg::StageFocused(focused) => {
+                self.slideshow.set_focused(focused);
+                self.slideshow.restart();
+                self.schedule_autoplay();
+            },
+            Msg::ToggleFullscreen => {
+                self.effect(Effect::ToggleFullscreen);
+            },
+            Msg::FullscreenChanged(fullscreen) => {
+                self.fullscreen = fullscreen;
+            },
+            Msg::DownloadCat => {
+                if let Some((url, name)) = self.current_cat_file().filter(|_| self.can_browse_cats()) {
+                    self.effect(Effect::Download { url, name });
+                }
+            },
+            Msg::CopyCat => {
+                if let Some((url, _)) = self.current_cat_file().filter(|_| self.can_browse_cats()) {
+                    self.effect(Effect::CopyImage { index: self.cats.current_index, url });
+                }
+            },
+            Msg::ShareCat => {
+                if let Some((url, name)) = self.current_cat_file().filter(|_| self.can_browse_cats()) {
+                    self.effect(Effect::Share { url, name });
+                }
+            },
+            Msg::CatActionFinished(status) => {
+                self.log(status.clone());
+                self.cat_action_status = Some(status);
+            },
+            Msg::ImageLoaded(generation, index) => {
+                self.cats.image_loaded(generation, ImageSlot::Current(index), &*self.platform);
+            },
+            Msg::NextBatchImageLoaded(generation, index) => {
+                self.cats.image_loaded(generation, ImageSlot::NextBatch(index), &*self.platform);
+            },
+            Msg::ImageFailed(generation, slot) => {
+                self.cats.image_failed(generation, slot, &*self.platform);
+            },
+            Msg::RetryImage(generation, slot) => {
+                self.cats.retry(generation, slot, &*self.platform);
             },
             Msg::NavigateTo(route) => {
-                console::log_1(&format!("Navigating to: {:?}", route).into());
-                self.navigate_to(route.clone());
-                self.current_route = route.clone();
-                
-                // Auto-load cats when navigating to cats page
-                if route == Route::Cats && self.cat_urls.is_empty() && !self.cat_loading {
-                    console::log_1(&"Auto-loading cats on route change".into());
-                    self.init_cat_loading();
+                if route != self.current_route {
+                    self.log(format!("Navigating to: {:?}", route));
+                    self.request_route(route, Navigation::Link);
                 }
             },
             Msg::UrlChanged(route) => {
-                console::log_1(&format!("URL changed to: {:?}", route).into());
-                self.current_route = route.clone();
-                
-                // Auto-load cats when URL changes to cats page
-                if route == Route::Cats && self.cat_urls.is_empty() && !self.cat_loading {
-                    console::log_1(&"Auto-loading cats on URL change".into());
-                    self.init_cat_loading();
+                // Our own NavigateTo also fires hashchange in hash mode, which is already handled
+                if route != self.current_route {
+                    self.log(format!("URL changed to: {:?}", route));
+                    self.request_route(route, Navigation::History);
+                }
+            },
+            Msg::ChangeRoute(route, navigation) => {
+                if route != self.current_route {
+                    self.change_route(route, navigation);
+                }
+            },
+            Msg::NavigationCancelled(navigation) => {
+                self.log("Navigation cancelled");
+                // The browser already moved on, so put the URL back
+                if navigation == Navigation::History {
+                    self.effect(Effect::ReplaceUrl(self.current_route.clone()));
                 }
             },
             // Games logic
             Msg::Game(game_msg) => {
-                self.games.update(game_msg);
+                self.games.update(game_msg, &mut *self.platform);
+                self.sync_game_url();
             },
         }
-        Cmd::none()
-    }
-
-    fn stylesheet() -> Vec<String> {
-        vec![]
+        if self.showing_cat_grid() {
+            self.effect(Effect::ObserveLazyImages);
+        }
+        self.take_effects()
     }
 }
 
 #[wasm_bindgen(start)]
 pub fn start() {
-    let app = App::new();
+    let bridge = Bridge::new();
+    let router = Router::from_document();
+    let app = App::from_document(bridge.clone(), router.clone());
+    let program = Program::mount_to_body(app);
+    bridge.attach(&program);
     
-    // Set up hashchange event listener for browser back/forward buttons and manual hash changes
-    if let Some(window) = window() {
-        let closure = Closure::wrap(Box::new(move |_event: web_sys::Event| {
-            console::log_1(&"Hash change detected".into());
-            // In a real implementation, we'd send Msg::UrlChanged here
-            // The app will automatically pick up the new route on next render
-        }) as Box<dyn Fn(web_sys::Event)>);
-        
-        let _ = window.add_event_listener_with_callback("hashchange", closure.as_ref().unchecked_ref());
-        closure.forget();
-    }
+    // Browser back/forward buttons and manually typed URLs re-route immediately
+    let url_router = router.clone();
+    bridge.listen_window(router.change_event(), move |_event| {
+        console::log_1(&"URL change detected".into());
+        Some(Msg::UrlChanged(url_router.current_route()))
+    });
+    
+    bridge.listen_window("fullscreenchange", |_event| Some(Msg::FullscreenChanged(slideshow::is_fullscreen())));
+    
+    // Left/right arrows browse cats, unless the user is typing into a field
+    bridge.listen_window("keydown", |event| {
+        let event = event.dyn_into::<web_sys::KeyboardEvent>().ok()?;
+        if event.alt_key() || event.ctrl_key() || event.meta_key() {
+            return None;
+        }
+        let typing = event.target()
+            .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+            .is_some_and(|element| matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT"));
+        if typing {
+            return None;
+        }
+        match event.key().as_str() {
+            "ArrowLeft" => Some(Msg::PreviousCat),
+            "ArrowRight" => Some(Msg::NextCat),
+            _ => None,
+        }
+    });
     
-    Program::mount_to_body(app);
+    router.track_scroll();
+    let scroll_router = router.clone();
+    bridge.listen_window("scroll", move |_event| {
+        scroll_router.record_scroll();
+        None
+    });
 }
@@ -0,0 +1,24 @@
+use web_sys::console;
+
+pub use counter_core::platform::{Clock, Log, Platform, Random};
+
+/// The core's randomness, clock and logging, provided by the browser
+pub struct BrowserPlatform;
+
+impl Random for BrowserPlatform {
+    fn next_f64(&mut self) -> f64 {
+        js_sys::Math::random()
+    }
+}
+
+impl Clock for BrowserPlatform {
+    fn now_ms(&self) -> f64 {
+        js_sys::Date::now()
+    }
+}
+
+impl Log for BrowserPlatform {
+    fn log(&self, message: &str) {
+        console::log_1(&message.into());
+    }
+}
@@ -0,0 +1,244 @@
+use sauron::{wasm_bindgen::closure::Closure, wasm_bindgen::JsCast, wasm_bindgen::JsValue};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use web_sys::{console, window, ScrollRestoration};
+
+pub use counter_core::routes::{Query, Route};
+
+/// Update `document.title` and the `<meta name="description">` for `route`
+pub fn set_document_meta(route: &Route) {
+    let Some(document) = window().and_then(|window| window.document()) else {
+        return;
+    };
+    document.set_title(&route.title());
+    if let Ok(Some(meta)) = document.query_selector("meta[name='description']") {
+        let _ = meta.set_attribute("content", route.description());
+    }
+}
+
+/// Per-route lifecycle, so page-specific side effects live in one place instead of at every
+/// spot that can change the route. Hooks queue effects rather than carrying them out.
+pub trait RouteHooks {
+    /// Called after `route` became the current route
+    fn on_enter(&mut self, _route: &Route) {}
+
+    /// Called once `route` has been replaced by the route being entered
+    fn on_leave(&mut self, _route: &Route) {}
+
+    /// Return a question for the user to veto navigating from `from` to `to`.
+    /// Navigation only proceeds if they confirm it.
+    fn leave_guard(&self, _from: &Route, _to: &Route) -> Option<String> {
+        None
+    }
+}
+
+/// Ask the user a yes/no question with the browser's confirm dialog
+pub fn confirm(question: &str) -> bool {
+    window()
+        .and_then(|window| window.confirm_with_message(question).ok())
+        .unwrap_or(true)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RouterMode {
+    /// Clean paths driven by `pushState`/`popstate`; needs a server that rewrites to `index.html`
+    History,
+    /// `#/...` fragments, works on any static host
+    Hash,
+}
+
+/// Maps routes to and from the browser URL.
+///
+/// Configured from `index.html`: `<meta name="router-mode" content="history|hash">` selects the
+/// mode and `<base href="/sub/dir/">` sets the path the app is served from.
+#[derive(Debug, Clone)]
+pub struct Router {
+    mode: RouterMode,
+    base_path: String,
+    /// Scroll offsets keyed by the id stored in each history entry's state
+    scroll_positions: Rc<RefCell<HashMap<String, f64>>>,
+}
+
+impl Router {
+    pub fn from_document() -> Self {
+        let mut router = Router {
+            mode: RouterMode::Hash,
+            base_path: String::new(),
+            scroll_positions: Rc::new(RefCell::new(HashMap::new())),
+        };
+
+        let Some(window) = window() else {
+            return router;
+        };
+        let Some(document) = window.document() else {
+            return router;
+        };
+
+        if let Ok(Some(base)) = document.query_selector("base") {
+            if let Some(href) = base.get_attribute("href") {
+                router.base_path = href.trim_end_matches('/').to_string();
+            }
+        }
+
+        let requested_history = document
+            .query_selector("meta[name='router-mode']")
+            .ok()
+            .flatten()
+            .and_then(|meta| meta.get_attribute("content"))
+            .is_some_and(|mode| mode == "history");
+
+        if requested_history {
+            // Pages opened from disk can't pushState to arbitrary paths
+            let served_over_http = window.location().protocol().is_ok_and(|protocol| protocol.starts_with("http"));
+            if served_over_http && window.history().is_ok() {
+                router.mode = RouterMode::History;
+            } else {
+                console::log_1(&"History API unavailable, falling back to hash routing".into());
+            }
+        }
+
+        console::log_1(&format!("Router mode: {:?}, base path: '{}'", router.mode, router.base_path).into());
+        router
+    }
+
+    /// The window event that signals the URL changed under us (back/forward, typed URL)
+    pub fn change_event(&self) -> &'static str {
+        match self.mode {
+            RouterMode::History => "popstate",
+            RouterMode::Hash => "hashchange",
+        }
+    }
+
+    pub fn current_route(&self) -> Route {
+        let Some(window) = window() else {
+            return Route::Home;
+        };
+        let location = window.location();
+        let hash = location.hash().unwrap_or_default();
+
+        match self.mode {
+            RouterMode::Hash => Route::from_hash(&hash),
+            RouterMode::History => {
+                // Links shared while the app was in hash mode still resolve, then get cleaned up
+                if hash.starts_with("#/") {
+                    let route = Route::from_hash(&hash);
+                    self.replace(&route);
+                    return route;
+                }
+                let pathname = location.pathname().unwrap_or_default();
+                let search = location.search().unwrap_or_default();
+                // `/sub` is the base of `/sub/cats` but not of `/subway`
+                match pathname.strip_prefix(&self.base_path) {
+                    Some(path) if path.is_empty() || path.starts_with('/') => Route::from_url(&format!("{}{}", path, search)),
+                    _ => Route::NotFound(format!("{}{}", pathname, search)),
+                }
+            }
+        }
+    }
+
+    pub fn navigate(&self, route: &Route) {
+        let Some(window) = window() else {
+            return;
+        };
+
+        match self.mode {
+            RouterMode::Hash => {
+                let hash = format!("#{}", route.to_url());
+                let _ = window.location().set_hash(&hash);
+                // set_hash adds a stateless entry, tag it so its scroll offset can be tracked
+                if let Ok(history) = window.history() {
+                    let _ = history.replace_state(&new_entry_state(), "");
+                }
+                console::log_1(&format!("Navigated to: {}", hash).into());
+            }
+            RouterMode::History => {
+                let url = self.url_for(route);
+                if let Ok(history) = window.history() {
+                    let _ = history.push_state_with_url(&new_entry_state(), "", Some(&url));
+                }
+                console::log_1(&format!("Navigated to: {}", url).into());
+            }
+        }
+
+        // New pages start at the top
+        scroll_after_render(0.0);
+    }
+
+    /// Take scroll restoration over from the browser, which can't know when the app re-renders.
+    /// Offsets get recorded by `record_scroll`, which should run on every window scroll.
+    pub fn track_scroll(&self) {
+        let Some(Ok(history)) = window().map(|window| window.history()) else {
+            return;
+        };
+        let _ = history.set_scroll_restoration(ScrollRestoration::Manual);
+        if current_entry_key().is_none() {
+            let _ = history.replace_state(&new_entry_state(), "");
+        }
+    }
+
+    pub fn record_scroll(&self) {
+        let Some(window) = window() else {
+            return;
+        };
+        if let (Some(key), Ok(offset)) = (current_entry_key(), window.scroll_y()) {
+            self.scroll_positions.borrow_mut().insert(key, offset);
+        }
+    }
+
+    /// Scroll back to where the current history entry was left, once the page has re-rendered
+    pub fn restore_scroll(&self) {
+        let offset = current_entry_key()
+            .and_then(|key| self.scroll_positions.borrow().get(&key).copied())
+            .unwrap_or(0.0);
+        scroll_after_render(offset);
+    }
+
+    /// Point the URL at `route` without adding a history entry
+    pub fn replace(&self, route: &Route) {
+        let Some(window) = window() else {
+            return;
+        };
+
+        let url = match self.mode {
+            RouterMode::Hash => format!("#{}", route.to_url()),
+            RouterMode::History => self.url_for(route),
+        };
+        // Keep the entry's state, it holds the key its scroll offset is recorded under
+        if let Ok(history) = window.history() {
+            let state = history.state().unwrap_or(JsValue::NULL);
+            let _ = history.replace_state_with_url(&state, "", Some(&url));
+        }
+    }
+
+    fn url_for(&self, route: &Route) -> String {
+        format!("{}{}", self.base_path, route.to_url())
+    }
+}
+
+const ENTRY_KEY: &str = "entryKey";
+
+/// History state carrying a fresh id for the entry about to be created
+fn new_entry_state() -> JsValue {
+    let key = format!("{}-{}", js_sys::Date::now(), js_sys::Math::random());
+    let state = js_sys::Object::new();
+    let _ = js_sys::Reflect::set(&state, &ENTRY_KEY.into(), &key.into());
+    state.into()
+}
+
+fn current_entry_key() -> Option<String> {
+    let state = window()?.history().ok()?.state().ok()?;
+    js_sys::Reflect::get(&state, &ENTRY_KEY.into()).ok()?.as_string()
+}
+
+/// The view is patched after `update` returns, so wait a frame before scrolling
+fn scroll_after_render(offset: f64) {
+    let Some(window) = window() else {
+        return;
+    };
+    let scroll_window = window.clone();
+    let callback = Closure::once_into_js(move || {
+        scroll_window.scroll_to_with_x_and_y(0.0, offset);
+    });
+    let _ = window.request_animation_frame(callback.unchecked_ref());
+}
@@ -0,0 +1,202 @@
+use sauron::wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    window, Blob, ClipboardItem, File, FilePropertyBag, HtmlAnchorElement, HtmlCanvasElement, HtmlImageElement, Navigator,
+    RequestCache, RequestInit, Response, ShareData, Url,
+};
+
+/// How long a downloaded blob stays available to the browser's download manager
+const REVOKE_AFTER_MS: i32 = 10_000;
+
+/// A file name for a cat image, e.g. `cat-Ab12Cd.jpg`, with the extension taken from its type
+pub fn file_name(name: &str, mime_type: &str) -> String {
+    let extension = match mime_type {
+        "image/jpeg" => "jpg",
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        "image/avif" => "avif",
+        _ => "img",
+    };
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    format!("cat-{}.{}", name, extension)
+}
+
+/// Save the cat at `url` as a file. Returns a message for the user either way.
+pub async fn download(url: String, name: String) -> String {
+    match fetch_blob(&url).await {
+        Ok(blob) => {
+            let file_name = file_name(&name, &blob.type_());
+            match save_blob(&blob, &file_name) {
+                Ok(()) => format!("Saved {}", file_name),
+                Err(_) => "Couldn't save the image".to_string(),
+            }
+        }
+        // Without CORS the bytes can't be read, so let the browser open the image itself
+        Err(_) => match open_in_new_tab(&url) {
+            Ok(()) => "Opened the image in a new tab, save it from there".to_string(),
+            Err(_) => "Couldn't download the image".to_string(),
+        },
+    }
+}
+
+/// Copy the image onto the clipboard, or its link where images can't be copied
+pub async fn copy_image(image: Option<HtmlImageElement>, url: String) -> String {
+    let Some(navigator) = navigator() else {
+        return "Copying isn't supported here".to_string();
+    };
+    if !has(&navigator, "clipboard") {
+        return "The clipboard isn't available (it needs HTTPS)".to_string();
+    }
+    let clipboard = navigator.clipboard();
+
+    // Browsers only reliably accept PNG images on the clipboard
+    if let Some(png) = image.and_then(|image| png_blob(&image)) {
+        let items = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&items, &"image/png".into(), &png);
+        if let Ok(item) = ClipboardItem::new_with_record_from_str_to_blob_promise(&items) {
+            if JsFuture::from(clipboard.write(&js_sys::Array::of1(&item))).await.is_ok() {
+                return "Image copied".to_string();
+            }
+        }
+    }
+
+    match copy_link(&url).await {
+        Ok(()) => "Image can't be copied here, copied its link instead".to_string(),
+        Err(_) => "Couldn't copy the image".to_string(),
+    }
+}
+
+/// Share through the system share sheet, falling back to copying the link or downloading
+pub async fn share(url: String, name: String) -> String {
+    let Some(navigator) = navigator() else {
+        return "Sharing isn't supported here".to_string();
+    };
+    if !has(&navigator, "share") {
+        return match copy_link(&url).await {
+            Ok(()) => "Sharing isn't supported here, copied the link instead".to_string(),
+            Err(_) => download(url, name).await,
+        };
+    }
+
+    let data = ShareData::new();
+    data.set_title("A cat for you");
+    if let Ok(blob) = fetch_blob(&url).await {
+        let options = FilePropertyBag::new();
+        options.set_type(&blob.type_());
+        let parts = js_sys::Array::of1(&blob);
+        if let Ok(file) = File::new_with_blob_sequence_and_options(&parts, &file_name(&name, &blob.type_()), &options) {
+            data.set_files(&js_sys::Array::of1(&file));
+        }
+    }
+    let can_share_files = has(&navigator, "canShare") && navigator.can_share_with_data(&data);
+    if !can_share_files {
+        if !is_shareable_link(&url) {
+            return download(url, name).await;
+        }
+        data.set_files(&JsValue::UNDEFINED);
+        data.set_url(&url);
+    }
+
+    match JsFuture::from(navigator.share_with_data(&data)).await {
+        Ok(_) => "Shared".to_string(),
+        // Also what closing the share sheet looks like
+        Err(_) => "Not shared".to_string(),
+    }
+}
+
+fn navigator() -> Option<Navigator> {
+    Some(window()?.navigator())
+}
+
+/// Feature detection; calling a missing browser API would throw
+fn has(navigator: &Navigator, property: &str) -> bool {
+    js_sys::Reflect::get(navigator, &property.into()).is_ok_and(|value| !value.is_undefined())
+}
+
+async fn copy_link(url: &str) -> Result<(), JsValue> {
+    let navigator = navigator().ok_or("no navigator")?;
+    if !has(&navigator, "clipboard") || !is_shareable_link(url) {
+        return Err("no link to copy".into());
+    }
+    JsFuture::from(navigator.clipboard().write_text(url)).await.map(|_| ())
+}
+
+/// Inline and object URLs only mean something inside this page
+fn is_shareable_link(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+async fn fetch_blob(url: &str) -> Result<Blob, JsValue> {
+    let window = window().ok_or("no window")?;
+    // Prefer the copy the browser already downloaded, random cats differ on every request
+    let init = RequestInit::new();
+    init.set_cache(RequestCache::ForceCache);
+    let response: Response = JsFuture::from(window.fetch_with_str_and_init(url, &init)).await?.dyn_into()?;
+    if !response.ok() {
+        return Err(format!("HTTP {}", response.status()).into());
+    }
+    JsFuture::from(response.blob()?).await?.dyn_into()
+}
+
+fn save_blob(blob: &Blob, file_name: &str) -> Result<(), JsValue> {
+    let object_url = Url::create_object_url_with_blob(blob)?;
+    let result = click_link(&object_url, Some(file_name));
+
+    // Some browsers start reading the blob only after the click returns
+    let revoke = Closure::once_into_js(move || {
+        let _ = Url::revoke_object_url(&object_url);
+    });
+    window()
+        .ok_or("no window")?
+        .set_timeout_with_callback_and_timeout_and_arguments_0(revoke.unchecked_ref(), REVOKE_AFTER_MS)?;
+    result
+}
+
+fn open_in_new_tab(url: &str) -> Result<(), JsValue> {
+    click_link(url, None)
+}
+
+fn click_link(href: &str, download: Option<&str>) -> Result<(), JsValue> {
+    let document = window().and_then(|window| window.document()).ok_or("no document")?;
+    let link: HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+    link.set_href(href);
+    match download {
+        Some(file_name) => link.set_download(file_name),
+        None => {
+            link.set_target("_blank");
+            link.set_rel("noopener");
+        }
+    }
+    link.click();
+    Ok(())
+}
+
+/// A promise of the image re-encoded as PNG, or `None` when the canvas can't read it
+fn png_blob(image: &HtmlImageElement) -> Option<js_sys::Promise> {
+    let (width, height) = (image.natural_width(), image.natural_height());
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let canvas: HtmlCanvasElement = window()?.document()?.create_element("canvas").ok()?.dyn_into().ok()?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context: web_sys::CanvasRenderingContext2d = canvas.get_context("2d").ok()??.dyn_into().ok()?;
+    context.draw_image_with_html_image_element(image, 0.0, 0.0).ok()?;
+    // Reading a tainted canvas throws here, before any promise is handed out
+    context.get_image_data(0.0, 0.0, 1.0, 1.0).ok()?;
+
+    Some(js_sys::Promise::new(&mut |resolve, reject| {
+        let reject_now = reject.clone();
+        let callback = Closure::once_into_js(move |blob: JsValue| {
+            let _ = if blob.is_null() { reject.call0(&JsValue::NULL) } else { resolve.call1(&JsValue::NULL, &blob) };
+        });
+        if canvas.to_blob(callback.unchecked_ref()).is_err() {
+            let _ = reject_now.call0(&JsValue::NULL);
+        }
+    }))
+}
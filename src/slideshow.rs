@@ -0,0 +1,87 @@
+use web_sys::{console, window};
+
+/// Intervals offered in the slideshow controls, in seconds
+pub const INTERVALS_S: [u32; 5] = [3, 5, 10, 30, 60];
+
+/// Id of the element shown fullscreen
+pub const STAGE_ID: &str = "cat-stage";
+
+/// Autoplay state of the cat browser.
+///
+/// Ticks are one-shot timers carrying the generation they were scheduled for; restarting
+/// or stopping bumps the generation, so a tick that's no longer wanted just does nothing
+/// when it fires instead of having to be cancelled.
+#[derive(Debug, Clone)]
+pub struct Slideshow {
+    pub playing: bool,
+    pub interval_s: u32,
+    hovered: bool,
+    focused: bool,
+    generation: u32,
+}
+
+impl Slideshow {
+    /// Stopped, unless the page was opened with `?autoplay=<seconds>`, e.g. on a lobby screen
+    pub fn from_query(seconds: Option<String>) -> Self {
+        let mut slideshow = Slideshow { playing: false, interval_s: 5, hovered: false, focused: false, generation: 0 };
+        if let Some(interval_s) = seconds.and_then(|seconds| seconds.trim().parse::<u32>().ok()).filter(|s| *s > 0) {
+            slideshow.playing = true;
+            slideshow.interval_s = interval_s;
+        }
+        slideshow
+    }
+
+    /// Playing and nobody is looking closely at the current cat
+    pub fn is_running(&self) -> bool {
+        self.playing && !self.hovered && !self.focused
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.playing && !self.is_running()
+    }
+
+    /// Invalidate pending ticks and return the generation for the next one
+    pub fn restart(&mut self) -> u32 {
+        self.generation = self.generation.wrapping_add(1);
+        self.generation
+    }
+
+    pub fn is_current(&self, generation: u32) -> bool {
+        self.generation == generation
+    }
+
+    pub fn interval_ms(&self) -> i32 {
+        (self.interval_s.saturating_mul(1000)).min(i32::MAX as u32) as i32
+    }
+
+    pub fn set_hovered(&mut self, hovered: bool) {
+        self.hovered = hovered;
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+}
+
+pub fn is_fullscreen() -> bool {
+    window()
+        .and_then(|window| window.document())
+        .is_some_and(|document| document.fullscreen_element().is_some())
+}
+
+/// Show the cat stage fullscreen, or leave fullscreen if something already is
+pub fn toggle_fullscreen() {
+    let Some(document) = window().and_then(|window| window.document()) else {
+        return;
+    };
+    if document.fullscreen_element().is_some() {
+        document.exit_fullscreen();
+        return;
+    }
+    let requested = document
+        .get_element_by_id(STAGE_ID)
+        .is_some_and(|stage| stage.request_fullscreen().is_ok());
+    if !requested {
+        console::log_1(&"Fullscreen is not available".into());
+    }
+}
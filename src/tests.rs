@@ -0,0 +1,189 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use sauron::{Application, Cmd};
+
+use crate::cats::{CatMetadata, CatSource, CatWindow, FixtureSource, PreloadSettings, SourceRequest};
+use crate::effects::{Effect, EffectRunner};
+use crate::games::{GameKind, GameMsg};
+use crate::platform::{Clock, Log, Random};
+use crate::router::{Query, Route};
+use crate::{App, Msg};
+use counter_core::cat_batches::{CatBatches, ImageAction};
+
+/// Keeps every effect it is handed instead of carrying it out
+#[derive(Clone, Default)]
+struct RecordingRunner {
+    ran: Rc<RefCell<Vec<Effect>>>,
+}
+
+impl RecordingRunner {
+    fn take(&self) -> Vec<Effect> {
+        std::mem::take(&mut *self.ran.borrow_mut())
+    }
+}
+
+impl EffectRunner for RecordingRunner {
+    fn run(&mut self, effects: Vec<Effect>) -> Cmd<Msg> {
+        self.ran.borrow_mut().extend(effects);
+        Cmd::none()
+    }
+}
+
+/// Always rolls 0.0 and never moves its clock; keeps what the core logs
+#[derive(Clone, Default)]
+struct QuietPlatform {
+    logs: Rc<RefCell<Vec<String>>>,
+}
+
+impl Random for QuietPlatform {
+    fn next_f64(&mut self) -> f64 {
+        0.0
+    }
+}
+
+impl Clock for QuietPlatform {
+    fn now_ms(&self) -> f64 {
+        0.0
+    }
+}
+
+impl Log for QuietPlatform {
+    fn log(&self, message: &str) {
+        self.logs.borrow_mut().push(message.to_string());
+    }
+}
+
+/// Every `metadata_fetched` call a source got
+type FetchedMetadata = Rc<RefCell<Vec<(u32, Option<CatMetadata>)>>>;
+
+/// Fixture cats, asking for metadata with every batch and to store every loaded cat
+#[derive(Default)]
+struct RequestingSource {
+    fixtures: FixtureSource,
+    requests: Vec<SourceRequest>,
+    fetched: FetchedMetadata,
+}
+
+impl CatSource for RequestingSource {
+    fn name(&self) -> &'static str {
+        "requesting"
+    }
+
+    fn next_batch(&mut self, count: usize) -> Vec<String> {
+        self.requests.push(SourceRequest::FetchMetadata { url: "/cat?json=true".to_string(), generation: 7 });
+        self.fixtures.next_batch(count)
+    }
+
+    fn image_loaded(&mut self, url: &str) {
+        self.requests.push(SourceRequest::Store(url.to_string()));
+    }
+
+    fn take_requests(&mut self) -> Vec<SourceRequest> {
+        std::mem::take(&mut self.requests)
+    }
+
+    fn metadata_fetched(&mut self, generation: u32, metadata: Option<CatMetadata>) {
+        self.fetched.borrow_mut().push((generation, metadata));
+    }
+}
+
+fn app(initial_route: Route) -> (App, RecordingRunner, QuietPlatform) {
+    app_with_source(Box::new(FixtureSource::new()), initial_route)
+}
+
+fn app_with_source(source: Box<dyn CatSource>, initial_route: Route) -> (App, RecordingRunner, QuietPlatform) {
+    let runner = RecordingRunner::default();
+    let platform = QuietPlatform::default();
+    let settings = PreloadSettings { batch_size: 3, look_ahead: 1, max_concurrent: 2, adaptive: false };
+    let cats = CatBatches::new(source, CatWindow::default(), settings);
+    let app = App::new(Box::new(platform.clone()), Box::new(runner.clone()), cats, initial_route);
+    (app, runner, platform)
+}
+
+#[test]
+fn leaving_a_memory_game_in_progress_asks_first() {
+    let memory = Route::Game { kind: GameKind::Memory, params: Query::default() };
+    let (mut app, runner, _) = app(memory.clone());
+    app.update(Msg::Game(GameMsg::StartMemoryGame));
+    app.update(Msg::Game(GameMsg::MemoryCardClick(0)));
+    runner.take();
+
+    app.update(Msg::NavigateTo(Route::Home));
+    let effects = runner.take();
+    assert!(matches!(
+        effects.as_slice(),
+        [Effect::Log(_), Effect::Confirm { confirmed: Msg::ChangeRoute(Route::Home, _), cancelled: Some(Msg::NavigationCancelled(_)), .. }]
+    ));
+    assert_eq!(app.current_route.section(), Route::games());
+
+    app.update(Msg::ChangeRoute(Route::Home, crate::Navigation::Link));
+    assert!(runner.take().iter().any(|effect| matches!(effect, Effect::PushUrl(Route::Home))));
+    assert_eq!(app.current_route, Route::Home);
+}
+
+#[test]
+fn fetching_cats_resets_the_images_and_starts_loads() {
+    let (mut app, runner, platform) = app(Route::Cats);
+    app.update(Msg::FetchCat);
+
+    let effects = runner.take();
+    assert!(matches!(effects.first(), Some(Effect::Log(_))));
+    let images: Vec<&ImageAction> = effects
+        .iter()
+        .filter_map(|effect| match effect {
+            Effect::Images(action) => Some(action),
+            _ => None,
+        })
+        .collect();
+    assert!(matches!(images.as_slice(), [ImageAction::Reset(3), ImageAction::Load { .. }, ImageAction::Load { .. }]));
+    assert!(platform.logs.borrow().iter().any(|line| line.contains("Starting to preload 3 images")));
+}
+
+#[test]
+fn autoplay_ticks_schedule_the_next_tick() {
+    let (mut app, runner, _) = app(Route::Cats);
+    app.update(Msg::ToggleAutoplay);
+    let Some(Effect::Timer { delay_ms, msg: Msg::AutoplayTick(generation) }) = runner.take().pop() else {
+        panic!("autoplay didn't schedule a tick");
+    };
+    assert_eq!(delay_ms, 5000);
+
+    app.update(Msg::AutoplayTick(generation));
+    let effects = runner.take();
+    assert!(matches!(effects.as_slice(), [Effect::Timer { delay_ms: 5000, msg: Msg::AutoplayTick(next) }] if *next != generation));
+
+    // A tick from before the restart does nothing
+    app.update(Msg::AutoplayTick(generation));
+    assert!(runner.take().is_empty());
+}
+
+#[test]
+fn links_to_cats_that_are_not_shown_point_the_url_at_the_shown_cat() {
+    let (mut app, runner, _) = app(Route::Home);
+    app.update(Msg::NavigateTo(Route::Cat("whiskers".to_string())));
+
+    let shown = Route::Cat("1".to_string());
+    assert!(runner.take().iter().any(|effect| matches!(effect, Effect::ReplaceUrl(route) if *route == shown)));
+    assert_eq!(app.current_route, shown);
+}
+
+#[test]
+fn cat_source_io_is_left_to_the_runner() {
+    let source = RequestingSource::default();
+    let fetched = source.fetched.clone();
+    let (mut app, runner, _) = app_with_source(Box::new(source), Route::Cats);
+    app.update(Msg::FetchCat);
+    let effects = runner.take();
+    assert!(effects.iter().any(|effect| matches!(effect, Effect::FetchCatMetadata { generation: 7, .. })));
+
+    let generation = app.cats.generation;
+    app.update(Msg::ImageLoaded(generation, 0));
+    assert!(runner.take().iter().any(|effect| matches!(effect, Effect::CacheCat(_))));
+
+    let metadata = CatMetadata { id: "abc".to_string(), tags: vec!["orange".to_string()] };
+    app.update(Msg::CatMetadataFetched(7, Ok(metadata.clone())));
+    app.update(Msg::CatMetadataFetched(7, Err("Couldn't fetch cat metadata: offline".to_string())));
+    assert_eq!(*fetched.borrow(), vec![(7, Some(metadata)), (7, None)]);
+    assert!(matches!(runner.take().as_slice(), [Effect::Log(line)] if line.contains("offline")));
+}